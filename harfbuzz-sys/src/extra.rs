@@ -0,0 +1,19 @@
+// Functions that are missing from the generated bindings in lib.rs,
+// because they were made public in a later HarfBuzz release than the
+// one the bindings were generated from.
+
+use std::os::raw::c_uint;
+
+use {hb_codepoint_t, hb_font_t};
+
+extern "C" {
+    /// Since: 2.6.3
+    pub fn hb_font_get_nominal_glyphs(
+        font: *mut hb_font_t,
+        count: c_uint,
+        first_unicode: *const hb_codepoint_t,
+        unicode_stride: c_uint,
+        first_glyph: *mut hb_codepoint_t,
+        glyph_stride: c_uint,
+    ) -> c_uint;
+}
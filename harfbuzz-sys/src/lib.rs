@@ -7,6 +7,9 @@ extern crate freetype;
 #[cfg(target_vendor = "apple")]
 pub mod coretext;
 
+mod extra;
+pub use extra::*;
+
 #[cfg(any(target_os = "android", all(unix, not(target_vendor = "apple"))))]
 extern "C" {
     pub fn hb_ft_font_create_referenced(face: freetype::freetype::FT_Face) -> *mut hb_font_t;
@@ -10,7 +10,7 @@
 use std;
 use sys;
 
-use {Direction, Language};
+use {Direction, Language, SegmentProperties};
 
 /// A series of Unicode characters.
 ///
@@ -133,6 +133,39 @@ impl Buffer {
         };
     }
 
+    /// Add the part of `text` in `item` to the buffer, using the rest
+    /// of `text` as context.
+    ///
+    /// The context before and after `item` is not shaped, but can
+    /// influence shaping of the item, for example to pick the correct
+    /// joining forms for Arabic. Clusters are byte offsets into `text`.
+    ///
+    /// ```
+    /// # use harfbuzz::Buffer;
+    /// let mut b = Buffer::new();
+    /// b.add_str_item("butterfly", 6..9);
+    /// assert_eq!(b.len(), 3);
+    /// assert_eq!(b.glyph_infos()[0].cluster, 6);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `item` is out of bounds, or its ends do not lie on
+    /// character boundaries.
+    pub fn add_str_item(&mut self, text: &str, item: std::ops::Range<usize>) {
+        assert!(text.is_char_boundary(item.start) && text.is_char_boundary(item.end));
+        assert!(item.start <= item.end);
+        unsafe {
+            sys::hb_buffer_add_utf8(
+                self.raw,
+                text.as_ptr() as *const std::os::raw::c_char,
+                text.len() as std::os::raw::c_int,
+                item.start as std::os::raw::c_uint,
+                (item.end - item.start) as std::os::raw::c_int,
+            )
+        };
+    }
+
     /// Append part of the contents of another buffer to this one.
     ///
     /// ```
@@ -298,6 +331,80 @@ impl Buffer {
     pub fn get_language(&self) -> Language {
         unsafe { Language::from_raw(sys::hb_buffer_get_language(self.raw)) }
     }
+
+    /// Sets the direction, script and language of the buffer at once.
+    ///
+    /// ```
+    /// # use harfbuzz::{Buffer, Direction, Language, SegmentProperties, sys};
+    /// let props = SegmentProperties {
+    ///     direction: Direction::RTL,
+    ///     script: sys::HB_SCRIPT_HEBREW,
+    ///     language: Language::from_string("he"),
+    /// };
+    /// let mut b = Buffer::with("שלום");
+    /// b.set_segment_properties(&props);
+    /// assert_eq!(b.segment_properties(), props);
+    /// assert_eq!(b.get_direction(), Direction::RTL);
+    /// ```
+    ///
+    /// See also:
+    ///
+    /// * [`segment_properties`](#method.segment_properties)
+    /// * [`guess_segment_properties`](#method.guess_segment_properties)
+    pub fn set_segment_properties(&mut self, props: &SegmentProperties) {
+        let props = (*props).into();
+        unsafe { sys::hb_buffer_set_segment_properties(self.raw, &props) };
+    }
+
+    /// Get the direction, script and language of the buffer.
+    ///
+    /// See also:
+    ///
+    /// * [`set_segment_properties`](#method.set_segment_properties)
+    pub fn segment_properties(&self) -> SegmentProperties {
+        unsafe {
+            let mut props = std::mem::zeroed();
+            sys::hb_buffer_get_segment_properties(self.raw, &mut props);
+            props.into()
+        }
+    }
+
+    /// Returns the glyph information of the buffer.
+    ///
+    /// Before shaping, each entry holds a Unicode codepoint of the
+    /// text. After shaping, each entry holds a glyph index in the
+    /// `codepoint` field, along with the cluster it belongs to.
+    ///
+    /// See also:
+    ///
+    /// * [`glyph_positions`](#method.glyph_positions)
+    pub fn glyph_infos(&self) -> &[sys::hb_glyph_info_t] {
+        unsafe {
+            let mut len = 0;
+            let ptr = sys::hb_buffer_get_glyph_infos(self.raw, &mut len);
+            if ptr.is_null() {
+                &[]
+            } else {
+                std::slice::from_raw_parts(ptr, len as usize)
+            }
+        }
+    }
+
+    /// Returns the glyph positions of the buffer.
+    ///
+    /// These are only meaningful after shaping, and correspond one to
+    /// one with the entries of [`glyph_infos`](#method.glyph_infos).
+    pub fn glyph_positions(&self) -> &[sys::hb_glyph_position_t] {
+        unsafe {
+            let mut len = 0;
+            let ptr = sys::hb_buffer_get_glyph_positions(self.raw, &mut len);
+            if ptr.is_null() {
+                &[]
+            } else {
+                std::slice::from_raw_parts(ptr, len as usize)
+            }
+        }
+    }
 }
 
 impl std::fmt::Debug for Buffer {
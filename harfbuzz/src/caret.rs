@@ -0,0 +1,384 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ops::Range;
+use sys;
+
+use {Direction, Font, GlyphRun};
+
+/// Returns the caret positions of a shaped run, as pairs of a byte
+/// offset into `text` and an x position relative to the left edge of
+/// the run, in logical order.
+///
+/// There is a caret at the start of each cluster of the run, and at
+/// its end. A cluster with fewer glyphs than grapheme clusters, such as
+/// a ligature, also has a caret at each grapheme boundary inside it.
+/// These are placed using the font's ligature caret data if it has
+/// any, or by dividing the cluster's advance equally between its
+/// graphemes otherwise. There are no carets between a base character
+/// and its combining marks.
+///
+/// `run` must have been shaped from `text` with `font`. The text is
+/// needed to find the grapheme boundaries, which a run doesn't keep.
+///
+/// ```
+/// # use harfbuzz::{caret_positions, hit_test, shape_paragraph, Blob, Direction, Face, Font};
+/// let blob = Blob::new_read_only(&[]);
+/// let face = Face::new(&blob, 0);
+/// let font = Font::new(&face);
+///
+/// // Each glyph of the empty font is 1000 units wide.
+/// let runs = shape_paragraph("ab", &font, Some(Direction::LTR), &[]);
+/// let carets = caret_positions("ab", &runs[0], &font);
+/// assert_eq!(carets, vec![(0, 0), (1, 1000), (2, 2000)]);
+/// assert_eq!(hit_test(&carets, 1400), 1);
+/// ```
+pub fn caret_positions(text: &str, run: &GlyphRun, font: &Font) -> Vec<(usize, i32)> {
+    let rtl = run.props.direction == Direction::RTL;
+    // The start of each cluster in logical order. A cluster covers the
+    // text up to the start of the next one.
+    let mut starts: Vec<usize> = run.infos.iter().map(|info| info.cluster as usize).collect();
+    starts.sort();
+    starts.dedup();
+
+    let mut carets = Vec::new();
+    let mut x = 0;
+    let mut i = 0;
+    while i < run.infos.len() {
+        let cluster = run.infos[i].cluster as usize;
+        let len = run.infos[i..]
+            .iter()
+            .take_while(|info| info.cluster as usize == cluster)
+            .count();
+        let width: i32 = run.positions[i..i + len]
+            .iter()
+            .map(|position| position.x_advance)
+            .sum();
+
+        let end = match starts.binary_search(&cluster) {
+            Ok(index) if index + 1 < starts.len() => starts[index + 1],
+            _ => run.range.end,
+        };
+        // Only clusters with fewer glyphs than graphemes, such as
+        // ligatures, have carets inside them.
+        let mut boundaries = graphemes(&text[cluster..end]);
+        if boundaries.len() <= len {
+            boundaries.truncate(1);
+        }
+        for offset in &mut boundaries {
+            *offset += cluster;
+        }
+
+        let ligature_carets = if len == 1 && boundaries.len() > 1 {
+            font.ligature_carets(run.props.direction, run.infos[i].codepoint)
+        } else {
+            Vec::new()
+        };
+        let count = boundaries.len() as i32;
+        for (k, &offset) in boundaries.iter().enumerate() {
+            // Ligature carets run from left to right, so the first
+            // caret of a right-to-left ligature is the rightmost one.
+            let caret = if ligature_carets.len() == boundaries.len() - 1 && k > 0 {
+                let index = if rtl { count as usize - 1 - k } else { k - 1 };
+                run.positions[i].x_offset + ligature_carets[index]
+            } else if rtl {
+                width - width * k as i32 / count
+            } else {
+                width * k as i32 / count
+            };
+            carets.push((offset, x + caret));
+        }
+
+        x += width;
+        i += len;
+    }
+    carets.push((run.range.end, if rtl { 0 } else { x }));
+    carets.sort_by_key(|&(offset, _)| offset);
+    carets
+}
+
+/// Returns the byte offsets of the starts of the grapheme clusters of
+/// `text`.
+///
+/// Like HarfBuzz, this only approximates the Unicode rules, by keeping
+/// marks, emoji modifiers, tags and zero width joiners with the
+/// character before them, along with a pictograph after a zero width
+/// joiner and a line feed after a carriage return. Regional indicators
+/// are paired into flags, and Hangul jamo into syllables.
+fn graphemes(text: &str) -> Vec<usize> {
+    let funcs = unsafe { sys::hb_unicode_funcs_get_default() };
+    let mut starts = Vec::new();
+    let mut previous = None;
+    // The number of regional indicators before this character.
+    let mut indicators = 0;
+    for (offset, c) in text.char_indices() {
+        let category = unsafe { sys::hb_unicode_general_category(funcs, c as u32) };
+        let extends = match category {
+            sys::HB_UNICODE_GENERAL_CATEGORY_NON_SPACING_MARK
+            | sys::HB_UNICODE_GENERAL_CATEGORY_SPACING_MARK
+            | sys::HB_UNICODE_GENERAL_CATEGORY_ENCLOSING_MARK => true,
+            sys::HB_UNICODE_GENERAL_CATEGORY_OTHER_SYMBOL if !is_regional_indicator(c) => {
+                previous == Some('\u{200D}')
+            }
+            _ => match c {
+                '\u{200D}' | '\u{1F3FB}'..='\u{1F3FF}' | '\u{E0020}'..='\u{E007F}' => true,
+                '\n' => previous == Some('\r'),
+                _ if is_regional_indicator(c) => indicators % 2 == 1,
+                _ => jamo_joins(previous, c),
+            },
+        };
+        if !extends || previous.is_none() {
+            starts.push(offset);
+        }
+        indicators = if is_regional_indicator(c) {
+            indicators + 1
+        } else {
+            0
+        };
+        previous = Some(c);
+    }
+    starts
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// The kinds of Hangul jamo and syllables, as in the Unicode
+/// `Hangul_Syllable_Type` property.
+#[derive(Clone, Copy)]
+enum Jamo {
+    L,
+    V,
+    T,
+    Lv,
+    Lvt,
+}
+
+fn jamo(c: char) -> Option<Jamo> {
+    match c {
+        '\u{1100}'..='\u{115F}' | '\u{A960}'..='\u{A97C}' => Some(Jamo::L),
+        '\u{1160}'..='\u{11A7}' | '\u{D7B0}'..='\u{D7C6}' => Some(Jamo::V),
+        '\u{11A8}'..='\u{11FF}' | '\u{D7CB}'..='\u{D7FB}' => Some(Jamo::T),
+        '\u{AC00}'..='\u{D7A3}' => {
+            // Precomposed syllables come in runs of 28 trailing
+            // consonants, the first of which is none.
+            let trailing = (c as u32 - 0xAC00) % 28;
+            Some(if trailing == 0 { Jamo::Lv } else { Jamo::Lvt })
+        }
+        _ => None,
+    }
+}
+
+/// Returns `true` if `c` continues the Hangul syllable ending with
+/// `previous`.
+fn jamo_joins(previous: Option<char>, c: char) -> bool {
+    matches!(
+        (previous.and_then(jamo), jamo(c)),
+        (Some(Jamo::L), Some(Jamo::L))
+            | (Some(Jamo::L), Some(Jamo::V))
+            | (Some(Jamo::L), Some(Jamo::Lv))
+            | (Some(Jamo::L), Some(Jamo::Lvt))
+            | (Some(Jamo::Lv), Some(Jamo::V))
+            | (Some(Jamo::Lv), Some(Jamo::T))
+            | (Some(Jamo::V), Some(Jamo::V))
+            | (Some(Jamo::V), Some(Jamo::T))
+            | (Some(Jamo::Lvt), Some(Jamo::T))
+            | (Some(Jamo::T), Some(Jamo::T))
+    )
+}
+
+/// Returns the byte offset of the caret nearest to `x`, given carets
+/// from [`caret_positions`].
+///
+/// # Panics
+///
+/// Panics if `carets` is empty.
+///
+/// [`caret_positions`]: fn.caret_positions.html
+pub fn hit_test(carets: &[(usize, i32)], x: i32) -> usize {
+    carets
+        .iter()
+        .min_by_key(|&&(_, caret)| (caret - x).abs())
+        .expect("no carets to hit test")
+        .0
+}
+
+/// Returns the x ranges covered by selecting the text in `selection`,
+/// from left to right.
+///
+/// `runs` are the glyph runs of a line in visual order, such as those
+/// of a line from `break_lines` or the result of [`shape_paragraph`],
+/// shaped from `text` with `font`. Positions are relative to the left edge of the
+/// first run. A logical selection in mixed-direction text can cover
+/// several separate ranges, and its ends can fall inside ligatures, in
+/// which case the range ends at the ligature caret as for
+/// [`caret_positions`].
+///
+/// ```
+/// # use harfbuzz::{selection_ranges, shape_paragraph, Blob, Direction, Face, Font};
+/// let blob = Blob::new_read_only(&[]);
+/// let face = Face::new(&blob, 0);
+/// let font = Font::new(&face);
+///
+/// // Each glyph of the empty font is 1000 units wide.
+/// let runs = shape_paragraph("abc", &font, Some(Direction::LTR), &[]);
+/// assert_eq!(selection_ranges("abc", &runs, &font, 1..3), vec![1000..3000]);
+/// ```
+///
+/// [`shape_paragraph`]: fn.shape_paragraph.html
+/// [`caret_positions`]: fn.caret_positions.html
+pub fn selection_ranges(
+    text: &str,
+    runs: &[GlyphRun],
+    font: &Font,
+    selection: Range<usize>,
+) -> Vec<Range<i32>> {
+    let mut ranges: Vec<Range<i32>> = Vec::new();
+    let mut x = 0;
+    for run in runs {
+        let start = selection.start.max(run.range.start);
+        let end = selection.end.min(run.range.end);
+        if start < end {
+            let carets = caret_positions(text, run, font);
+            let caret = |offset: usize| {
+                carets
+                    .iter()
+                    .find(|&&(caret, _)| caret >= offset)
+                    .map_or(0, |&(_, caret_x)| caret_x)
+            };
+            let (a, b) = (x + caret(start), x + caret(end));
+            let range = a.min(b)..a.max(b);
+            match ranges.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _ => ranges.push(range),
+            }
+        }
+        x += run.advance;
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+    use std::ops::Range;
+
+    use super::{caret_positions, graphemes, hit_test, selection_ranges};
+    use {sys, Blob, Direction, Face, Font, GlyphRun, Language, SegmentProperties};
+
+    static LCAR: &[u8] = include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/lcar.ttf");
+
+    fn ligature_run(glyph: u32, direction: Direction) -> GlyphRun {
+        run(glyph, 0..3, direction)
+    }
+
+    fn run(glyph: u32, range: Range<usize>, direction: Direction) -> GlyphRun {
+        let mut info: sys::hb_glyph_info_t = unsafe { mem::zeroed() };
+        info.codepoint = glyph;
+        info.cluster = range.start as u32;
+        let mut position: sys::hb_glyph_position_t = unsafe { mem::zeroed() };
+        position.x_advance = 3000;
+        GlyphRun {
+            range,
+            props: SegmentProperties {
+                direction,
+                script: sys::HB_SCRIPT_LATIN,
+                language: Language::from_string("en"),
+            },
+            level: 0,
+            infos: vec![info],
+            positions: vec![position],
+            advance: 3000,
+        }
+    }
+
+    #[test]
+    fn test_ligature_carets() {
+        let blob = Blob::new_read_only(LCAR);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        // Glyph 98 has two carets.
+        let carets = caret_positions("abc", &ligature_run(98, Direction::LTR), &font);
+        assert_eq!(carets, vec![(0, 0), (1, 565), (2, 1172), (3, 3000)]);
+        assert_eq!(hit_test(&carets, 800), 1);
+        assert_eq!(hit_test(&carets, 2500), 3);
+        let carets = caret_positions("abc", &ligature_run(98, Direction::RTL), &font);
+        assert_eq!(carets, vec![(0, 3000), (1, 1172), (2, 565), (3, 0)]);
+
+        // Other glyphs are divided equally.
+        let carets = caret_positions("abc", &ligature_run(1, Direction::LTR), &font);
+        assert_eq!(carets, vec![(0, 0), (1, 1000), (2, 2000), (3, 3000)]);
+        let carets = caret_positions("abc", &ligature_run(1, Direction::RTL), &font);
+        assert_eq!(carets, vec![(0, 3000), (1, 2000), (2, 1000), (3, 0)]);
+    }
+
+    #[test]
+    fn test_graphemes() {
+        let blob = Blob::new_read_only(LCAR);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        // A base and its combining mark are a single grapheme, whether
+        // shaped to one glyph or a ligature with another character.
+        let carets = caret_positions("e\u{301}", &run(1, 0..3, Direction::LTR), &font);
+        assert_eq!(carets, vec![(0, 0), (3, 3000)]);
+        let carets = caret_positions("e\u{301}f", &run(1, 0..4, Direction::LTR), &font);
+        assert_eq!(carets, vec![(0, 0), (3, 1500), (4, 3000)]);
+
+        assert_eq!(graphemes("a\r\nb\u{300}\u{301}"), vec![0, 1, 3]);
+        assert_eq!(
+            graphemes("\u{1F468}\u{200D}\u{1F469}\u{1F3FB}x"),
+            vec![0, 15]
+        );
+        assert_eq!(graphemes("\u{301}a"), vec![0, 2]);
+
+        // Regional indicators pair up into flags.
+        let flags = "\u{1F1FA}\u{1F1F8}\u{1F1EB}\u{1F1F7}\u{1F1EA}";
+        assert_eq!(graphemes(flags), vec![0, 8, 16]);
+        let carets = caret_positions(flags, &run(1, 0..20, Direction::LTR), &font);
+        assert_eq!(carets, vec![(0, 0), (8, 1000), (16, 2000), (20, 3000)]);
+
+        // Hangul jamo make up syllables, precomposed or not.
+        assert_eq!(graphemes("\u{1100}\u{1161}\u{11A8}"), vec![0]);
+        assert_eq!(
+            graphemes("\u{AC01}\u{1100}\u{1161}\u{AC00}\u{11A8}"),
+            vec![0, 3, 9]
+        );
+        assert_eq!(graphemes("\u{AC01}\u{1161}"), vec![0, 3]);
+        let carets = caret_positions(
+            "\u{1100}\u{1161}\u{11A8}",
+            &run(1, 0..9, Direction::LTR),
+            &font,
+        );
+        assert_eq!(carets, vec![(0, 0), (9, 3000)]);
+    }
+
+    #[test]
+    fn test_selection_ranges() {
+        let blob = Blob::new_read_only(LCAR);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        // Three ligatures of three characters each, the last two right to
+        // left, in visual order.
+        let text = "abcdefghi";
+        let runs = vec![
+            run(1, 0..3, Direction::LTR),
+            run(1, 6..9, Direction::RTL),
+            run(1, 3..6, Direction::RTL),
+        ];
+        let select = |range| selection_ranges(text, &runs, &font, range);
+        assert_eq!(select(0..9), vec![0..9000]);
+        assert_eq!(select(1..4), vec![1000..3000, 8000..9000]);
+        assert_eq!(select(5..8), vec![4000..7000]);
+        assert_eq!(select(4..4), vec![]);
+    }
+}
@@ -0,0 +1,295 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::marker::PhantomData;
+use std::os::raw::c_void;
+use std::{mem, ptr};
+use sys;
+
+use {Blob, Set, Tag};
+
+/// A font face is an object that represents a single face from
+/// within a font family.
+///
+/// More precisely, a font face represents a single face in a binary
+/// font file. Font faces are typically built from a binary blob and
+/// a face index.
+///
+/// A `Face` borrows the data of the blob it was created from, so it
+/// cannot outlive it.
+pub struct Face<'a> {
+    raw: *mut sys::hb_face_t,
+    phantom: PhantomData<&'a [u8]>,
+}
+
+impl<'a> Face<'a> {
+    /// Create a face from the face at `index` within `blob`.
+    ///
+    /// If the blob does not contain a valid font, the resulting face
+    /// will be empty rather than this call failing.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Face};
+    /// let blob = Blob::new_read_only(&[]);
+    /// let face = Face::new(&blob, 0);
+    /// assert_eq!(face.glyph_count(), 0);
+    /// ```
+    pub fn new(blob: &Blob<'a>, index: u32) -> Face<'a> {
+        unsafe { Face::from_raw(sys::hb_face_create(blob.as_raw(), index)) }
+    }
+
+    /// Create a face whose tables are provided on demand by `f`.
+    ///
+    /// HarfBuzz calls `f` with the tag of each table the first time it
+    /// is needed, so tables that are never used are never loaded.
+    /// Returning `None` means the face has no such table. `f` may also
+    /// be called with a zero tag when the whole font file is requested.
+    ///
+    /// `f` is dropped when the last reference to the face is dropped.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Face, Tag};
+    /// let face = Face::from_table_fn(|tag| {
+    ///     if tag == Tag::from_string("maxp") {
+    ///         // A version 0.5 `maxp` table with 42 glyphs.
+    ///         Some(Blob::new_from_arc_vec(vec![0, 0, 0x50, 0, 0, 42].into()))
+    ///     } else {
+    ///         None
+    ///     }
+    /// });
+    /// assert_eq!(face.glyph_count(), 42);
+    /// ```
+    pub fn from_table_fn<F>(f: F) -> Face<'static>
+    where
+        F: Fn(Tag) -> Option<Blob<'static>> + Send + Sync + 'static,
+    {
+        // This has type hb_reference_table_func_t
+        unsafe extern "C" fn reference_table<F>(
+            _: *mut sys::hb_face_t,
+            tag: sys::hb_tag_t,
+            user_data: *mut c_void,
+        ) -> *mut sys::hb_blob_t
+        where
+            F: Fn(Tag) -> Option<Blob<'static>>,
+        {
+            let f = &*(user_data as *const F);
+            match f(Tag(tag)) {
+                Some(blob) => blob.into_raw(),
+                None => ptr::null_mut(),
+            }
+        }
+
+        // This has type hb_destroy_func_t
+        unsafe extern "C" fn destroy<F>(user_data: *mut c_void) {
+            drop(Box::from_raw(user_data as *mut F))
+        }
+
+        unsafe {
+            Face::from_raw(sys::hb_face_create_for_tables(
+                Some(reference_table::<F>),
+                Box::into_raw(Box::new(f)) as *mut c_void,
+                Some(destroy::<F>),
+            ))
+        }
+    }
+
+    /// Returns the number of faces in a blob.
+    pub fn count(blob: &Blob) -> u32 {
+        unsafe { sys::hb_face_count(blob.as_raw()) }
+    }
+
+    /// Construct a `Face` from a raw pointer. Takes ownership of the face.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid `hb_face_t` that the caller owns a
+    /// reference to, which is released when the `Face` is dropped. The
+    /// data the face reads its tables from must outlive `'a`.
+    pub unsafe fn from_raw(raw: *mut sys::hb_face_t) -> Self {
+        Face {
+            raw,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Borrows a raw pointer to the face.
+    pub fn as_raw(&self) -> *mut sys::hb_face_t {
+        self.raw
+    }
+
+    /// Gives up ownership and returns a raw pointer to the face.
+    pub fn into_raw(self) -> *mut sys::hb_face_t {
+        let raw = self.raw;
+        mem::forget(self);
+        raw
+    }
+
+    /// Returns the index of this face within its blob.
+    pub fn index(&self) -> u32 {
+        unsafe { sys::hb_face_get_index(self.raw) }
+    }
+
+    /// Returns the units-per-em of the face.
+    pub fn upem(&self) -> u32 {
+        unsafe { sys::hb_face_get_upem(self.raw) }
+    }
+
+    /// Returns the number of glyphs in the face.
+    pub fn glyph_count(&self) -> u32 {
+        unsafe { sys::hb_face_get_glyph_count(self.raw) }
+    }
+
+    /// Returns the data of the table identified by `tag`, or `None` if
+    /// the face has no such table.
+    pub fn table(&self, tag: Tag) -> Option<Blob<'a>> {
+        let blob = unsafe { Blob::from_raw(sys::hb_face_reference_table(self.raw, tag.into())) };
+        if blob.is_empty() {
+            None
+        } else {
+            Some(blob)
+        }
+    }
+
+    /// Returns the tags of all tables in the face.
+    ///
+    /// This is empty for faces that were not created from a blob.
+    pub fn table_tags(&self) -> Vec<Tag> {
+        unsafe {
+            let count = sys::hb_face_get_table_tags(self.raw, 0, ptr::null_mut(), ptr::null_mut());
+            let mut tags = vec![0; count as usize];
+            let mut len = count;
+            sys::hb_face_get_table_tags(self.raw, 0, &mut len, tags.as_mut_ptr());
+            tags.truncate(len as usize);
+            tags.into_iter().map(Tag).collect()
+        }
+    }
+
+    /// Returns all Unicode codepoints the face's character map covers.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Face};
+    /// let face = Face::new(&Blob::new_read_only(&[]), 0);
+    /// assert!(face.unicodes().is_empty());
+    /// ```
+    pub fn unicodes(&self) -> Set {
+        let set = Set::new();
+        unsafe { sys::hb_face_collect_unicodes(self.raw, set.as_raw()) };
+        set
+    }
+
+    /// Returns all Unicode variation selectors the face's character map
+    /// has variation sequences for.
+    pub fn variation_selectors(&self) -> Set {
+        let set = Set::new();
+        unsafe { sys::hb_face_collect_variation_selectors(self.raw, set.as_raw()) };
+        set
+    }
+
+    /// Returns all Unicode codepoints that have a variation sequence
+    /// with `selector` in the face's character map.
+    pub fn variation_unicodes(&self, selector: u32) -> Set {
+        let set = Set::new();
+        unsafe { sys::hb_face_collect_variation_unicodes(self.raw, selector, set.as_raw()) };
+        set
+    }
+
+    /// Returns the blob that backs the face.
+    ///
+    /// For faces created by a [`FaceBuilder`], this is a freshly
+    /// compiled font file containing all the tables that were added.
+    ///
+    /// [`FaceBuilder`]: struct.FaceBuilder.html
+    pub fn blob(&self) -> Blob<'a> {
+        unsafe { Blob::from_raw(sys::hb_face_reference_blob(self.raw)) }
+    }
+}
+
+// HarfBuzz faces are reference counted atomically and load their
+// tables lazily in a thread-safe manner. Table callbacks are required
+// to be `Send + Sync`.
+unsafe impl<'a> Send for Face<'a> {}
+unsafe impl<'a> Sync for Face<'a> {}
+
+impl<'a> Clone for Face<'a> {
+    /// Increment the reference count, sharing the underlying face.
+    fn clone(&self) -> Self {
+        unsafe { Face::from_raw(sys::hb_face_reference(self.raw)) }
+    }
+}
+
+impl<'a> Drop for Face<'a> {
+    /// Decrement the reference count, and destroy the face if the reference count is zero.
+    fn drop(&mut self) {
+        unsafe {
+            sys::hb_face_destroy(self.raw);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Face;
+    use std::sync::{Arc, Mutex};
+    use {Blob, Tag};
+
+    static ROBOTO: &[u8] =
+        include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.abc.ttf");
+    static CMAP14: &[u8] =
+        include_bytes!("../../harfbuzz-sys/harfbuzz/test/subset/data/fonts/cmap14_font1.otf");
+
+    #[test]
+    fn test_from_table_fn() {
+        let original = Face::new(&Blob::new_read_only(ROBOTO), 0);
+        let tables: Vec<(Tag, Vec<u8>)> = original
+            .table_tags()
+            .into_iter()
+            .map(|tag| (tag, original.table(tag).unwrap().to_vec()))
+            .collect();
+        let requested = Arc::new(Mutex::new(Vec::new()));
+
+        let log = requested.clone();
+        let face = Face::from_table_fn(move |tag| {
+            log.lock().unwrap().push(tag);
+            tables
+                .iter()
+                .find(|(t, _)| *t == tag)
+                .map(|(_, data)| Blob::new_from_arc_vec(Arc::new(data.clone())))
+        });
+        assert_eq!(face.glyph_count(), original.glyph_count());
+        assert_eq!(face.upem(), original.upem());
+        assert!(face.table(Tag::from_string("zzzz")).is_none());
+
+        {
+            let requested = requested.lock().unwrap();
+            assert!(requested.contains(&Tag::from_string("maxp")));
+            assert!(requested.contains(&Tag::from_string("head")));
+            assert!(!requested.contains(&Tag::from_string("GSUB")));
+        }
+
+        // Dropping the face drops the closure and its captures.
+        assert_eq!(Arc::strong_count(&requested), 2);
+        drop(face);
+        assert_eq!(Arc::strong_count(&requested), 1);
+    }
+
+    #[test]
+    fn test_coverage() {
+        let face = Face::new(&Blob::new_read_only(CMAP14), 0);
+        assert_eq!(face.unicodes(), (0x4E00..=0x4E09).collect());
+        assert_eq!(
+            face.variation_selectors().iter().collect::<Vec<_>>(),
+            vec![0xE0100]
+        );
+
+        let sequences = face.variation_unicodes(0xE0100);
+        assert!(sequences.contains(0x4E00));
+        assert!(!sequences.contains(0x4E01));
+        assert!(face.variation_unicodes(0xFE00).is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::iter::FromIterator;
+use sys;
+
+use {Blob, Face, Tag};
+
+/// Assembles a font face from individual tables.
+///
+/// Tables are added as `(Tag, Blob)` pairs. The result can be used
+/// directly as a [`Face`], or compiled into a binary font file with a
+/// sorted table directory and correct checksums.
+///
+/// ```
+/// # use harfbuzz::{Blob, FaceBuilder, Tag};
+/// let name = [0u8; 6];
+/// let mut builder = FaceBuilder::new();
+/// builder.add_table(Tag::from_string("name"), Blob::new_read_only(&name));
+///
+/// let font = builder.to_blob();
+/// // 12 byte header, one 16 byte table record and the padded table.
+/// assert_eq!(font.len(), 12 + 16 + 8);
+/// assert_eq!(&font[12..16], b"name");
+///
+/// let face = builder.build();
+/// assert_eq!(face.table(Tag::from_string("name")).unwrap().len(), 6);
+/// ```
+///
+/// [`Face`]: struct.Face.html
+pub struct FaceBuilder<'a> {
+    face: Face<'a>,
+}
+
+impl<'a> FaceBuilder<'a> {
+    /// Create a new builder with no tables.
+    pub fn new() -> Self {
+        FaceBuilder {
+            face: unsafe { Face::from_raw(sys::hb_face_builder_create()) },
+        }
+    }
+
+    /// Add the table identified by `tag` with the contents of `blob`.
+    ///
+    /// Tables should only be added once for each tag.
+    pub fn add_table(&mut self, tag: Tag, blob: Blob<'a>) {
+        // This only fails if the builder could not be allocated, in
+        // which case the face is the empty face.
+        unsafe { sys::hb_face_builder_add_table(self.face.as_raw(), tag.into(), blob.as_raw()) };
+    }
+
+    /// Compile the tables added so far into a binary font file.
+    ///
+    /// The tables are written in tag order, and the `head` table's
+    /// `checkSumAdjustment` is updated if present. The returned blob
+    /// owns its data.
+    pub fn to_blob(&self) -> Blob<'static> {
+        unsafe {
+            let raw = sys::hb_face_reference_blob(self.face.as_raw());
+            if raw.is_null() {
+                Blob::from_raw(sys::hb_blob_get_empty())
+            } else {
+                Blob::from_raw(raw)
+            }
+        }
+    }
+
+    /// Finish building and return the face.
+    ///
+    /// Tables are looked up directly from the added blobs rather than
+    /// being compiled into a font file first.
+    pub fn build(self) -> Face<'a> {
+        self.face
+    }
+}
+
+impl<'a> Default for FaceBuilder<'a> {
+    fn default() -> Self {
+        FaceBuilder::new()
+    }
+}
+
+impl<'a> Extend<(Tag, Blob<'a>)> for FaceBuilder<'a> {
+    fn extend<I: IntoIterator<Item = (Tag, Blob<'a>)>>(&mut self, iter: I) {
+        for (tag, blob) in iter {
+            self.add_table(tag, blob);
+        }
+    }
+}
+
+impl<'a> FromIterator<(Tag, Blob<'a>)> for FaceBuilder<'a> {
+    fn from_iter<I: IntoIterator<Item = (Tag, Blob<'a>)>>(iter: I) -> Self {
+        let mut builder = FaceBuilder::new();
+        builder.extend(iter);
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FaceBuilder;
+    use {Blob, Face, Tag};
+
+    static ROBOTO: &[u8] =
+        include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.abc.ttf");
+
+    fn checksum(data: &[u8]) -> u32 {
+        data.chunks(4).fold(0u32, |sum, chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            sum.wrapping_add(u32::from_be_bytes(word))
+        })
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let original = Face::new(&Blob::new_read_only(ROBOTO), 0);
+        let tags = original.table_tags();
+        assert!(!tags.is_empty());
+
+        // Add the tables in reverse to check they get sorted.
+        let builder: FaceBuilder = tags
+            .iter()
+            .rev()
+            .map(|&tag| (tag, original.table(tag).unwrap()))
+            .collect();
+        let font = builder.to_blob();
+
+        let rebuilt = Face::new(&font, 0);
+        let mut sorted = tags.clone();
+        sorted.sort();
+        assert_eq!(rebuilt.table_tags(), sorted);
+        assert_eq!(rebuilt.glyph_count(), original.glyph_count());
+        for &tag in &tags {
+            if tag != Tag::from_string("head") {
+                assert_eq!(
+                    &*rebuilt.table(tag).unwrap(),
+                    &*original.table(tag).unwrap()
+                );
+            }
+        }
+
+        // Each table record's checksum matches its table, and the
+        // whole file sums to the magic value.
+        for record in font[12..12 + 16 * tags.len()].chunks(16) {
+            let sum = u32::from_be_bytes([record[4], record[5], record[6], record[7]]);
+            let offset =
+                u32::from_be_bytes([record[8], record[9], record[10], record[11]]) as usize;
+            let len = u32::from_be_bytes([record[12], record[13], record[14], record[15]]) as usize;
+            if &record[..4] != b"head" {
+                assert_eq!(checksum(&font[offset..offset + len]), sum);
+            }
+        }
+        assert_eq!(checksum(&font), 0xB1B0_AFBA);
+    }
+}
@@ -0,0 +1,311 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ops::Range;
+use sys;
+
+use {shape, Buffer, Direction, Feature, Font, SegmentProperties};
+
+/// A glyph shaped by a [`FallbackShaper`].
+///
+/// [`FallbackShaper`]: struct.FallbackShaper.html
+#[derive(Copy, Clone, Debug)]
+pub struct FallbackGlyph {
+    /// The index of the font the glyph belongs to, within the fonts of
+    /// the `FallbackShaper`.
+    pub font_index: usize,
+    /// The glyph index and the cluster it belongs to. Clusters are byte
+    /// offsets into the shaped text.
+    pub info: sys::hb_glyph_info_t,
+    /// The position of the glyph.
+    pub position: sys::hb_glyph_position_t,
+}
+
+/// Shapes text with an ordered list of fonts, falling back to later
+/// fonts for clusters that earlier ones cannot display.
+///
+/// Text is first shaped with the primary font. Each contiguous range
+/// of clusters that contains a `.notdef` glyph is then reshaped with
+/// the next font that covers any of its characters, with the
+/// surrounding text given as context, and the results are spliced
+/// into a single run. Clusters that no font covers keep the primary
+/// font's `.notdef` glyphs.
+///
+/// ```
+/// # use harfbuzz::{sys, Blob, Direction, Face, FallbackShaper, Font, Language, SegmentProperties};
+/// let blob = Blob::new_read_only(&[]);
+/// let face = Face::new(&blob, 0);
+/// let shaper = FallbackShaper::new(vec![Font::new(&face), Font::new(&face)]);
+/// let props = SegmentProperties {
+///     direction: Direction::LTR,
+///     script: sys::HB_SCRIPT_LATIN,
+///     language: Language::from_string("en"),
+/// };
+/// let glyphs = shaper.shape("abc", &props, &[]);
+/// assert_eq!(glyphs.len(), 3);
+/// assert!(glyphs.iter().all(|g| g.font_index == 0 && g.info.codepoint == 0));
+/// ```
+pub struct FallbackShaper<'a> {
+    fonts: Vec<Font<'a>>,
+}
+
+impl<'a> FallbackShaper<'a> {
+    /// Create a shaper that tries `fonts` in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fonts` is empty.
+    pub fn new(fonts: Vec<Font<'a>>) -> Self {
+        assert!(
+            !fonts.is_empty(),
+            "a fallback shaper needs at least one font"
+        );
+        FallbackShaper { fonts }
+    }
+
+    /// Returns the fonts of the shaper, in fallback order.
+    pub fn fonts(&self) -> &[Font<'a>] {
+        &self.fonts
+    }
+
+    /// Shape `text` with `props` and `features`, returning the glyphs in
+    /// visual order, each tagged with the font it came from.
+    pub fn shape(
+        &self,
+        text: &str,
+        props: &SegmentProperties,
+        features: &[Feature],
+    ) -> Vec<FallbackGlyph> {
+        self.shape_item(text, 0..text.len(), 0, props, features)
+    }
+
+    fn shape_item(
+        &self,
+        text: &str,
+        item: Range<usize>,
+        font_index: usize,
+        props: &SegmentProperties,
+        features: &[Feature],
+    ) -> Vec<FallbackGlyph> {
+        let font = &self.fonts[font_index];
+        let mut buffer = Buffer::new();
+        buffer.add_str_item(text, item.clone());
+        buffer.set_segment_properties(props);
+        shape(font, &mut buffer, features);
+
+        let glyphs: Vec<FallbackGlyph> = buffer
+            .glyph_infos()
+            .iter()
+            .zip(buffer.glyph_positions())
+            .map(|(&info, &position)| FallbackGlyph {
+                font_index,
+                info,
+                position,
+            })
+            .collect();
+
+        let missing = missing_ranges(&glyphs, item.end);
+        if missing.is_empty() {
+            return glyphs;
+        }
+
+        let backward = props.direction == Direction::RTL || props.direction == Direction::BTT;
+        splice(&glyphs, &missing, backward, |range, original| {
+            let next = match self.fallback_index(&text[range.clone()], font_index) {
+                Some(next) => next,
+                None => return original.to_vec(),
+            };
+            // Put back the glyphs of the clusters that the later fonts
+            // don't cover either.
+            let replacement = self.shape_item(text, range.clone(), next, props, features);
+            let still_missing = missing_ranges(&replacement, range.end);
+            splice(&replacement, &still_missing, backward, |range, glyphs| {
+                let kept: Vec<FallbackGlyph> = original
+                    .iter()
+                    .filter(|g| range.contains(&(g.info.cluster as usize)))
+                    .cloned()
+                    .collect();
+                if kept.is_empty() {
+                    glyphs.to_vec()
+                } else {
+                    kept
+                }
+            })
+        })
+    }
+
+    /// Returns the first font after `font_index` that covers any of the
+    /// characters in `text`.
+    fn fallback_index(&self, text: &str, font_index: usize) -> Option<usize> {
+        (font_index + 1..self.fonts.len()).find(|&index| {
+            text.chars()
+                .any(|c| self.fonts[index].nominal_glyph(c).is_some())
+        })
+    }
+}
+
+/// Returns `glyphs` with the glyphs of each range of clusters in
+/// `missing` replaced by the result of `replace`.
+///
+/// `glyphs` are in visual order, so their clusters are in ascending
+/// order, or descending if `backward`. `missing` is in logical order.
+fn splice<F>(
+    glyphs: &[FallbackGlyph],
+    missing: &[Range<usize>],
+    backward: bool,
+    mut replace: F,
+) -> Vec<FallbackGlyph>
+where
+    F: FnMut(Range<usize>, &[FallbackGlyph]) -> Vec<FallbackGlyph>,
+{
+    let mut ranges: Vec<&Range<usize>> = missing.iter().collect();
+    if backward {
+        ranges.reverse();
+    }
+    let mut ranges = ranges.into_iter().peekable();
+
+    let mut result = Vec::with_capacity(glyphs.len());
+    let mut i = 0;
+    while i < glyphs.len() {
+        let cluster = glyphs[i].info.cluster as usize;
+        // Skip the ranges that come before this glyph in visual order.
+        while let Some(range) = ranges.peek() {
+            let passed = if backward {
+                cluster < range.start
+            } else {
+                range.end <= cluster
+            };
+            if !passed {
+                break;
+            }
+            ranges.next();
+        }
+        let range = match ranges.peek() {
+            Some(range) if range.contains(&cluster) => (*range).clone(),
+            _ => {
+                result.push(glyphs[i]);
+                i += 1;
+                continue;
+            }
+        };
+
+        // The glyphs of a range of clusters are contiguous.
+        let end = i + glyphs[i..]
+            .iter()
+            .take_while(|g| range.contains(&(g.info.cluster as usize)))
+            .count();
+        result.extend(replace(range, &glyphs[i..end]));
+        ranges.next();
+        i = end;
+    }
+    result
+}
+
+/// Returns the byte ranges of contiguous clusters that contain a
+/// `.notdef` glyph, in logical order.
+fn missing_ranges(glyphs: &[FallbackGlyph], end: usize) -> Vec<Range<usize>> {
+    let mut clusters: Vec<(usize, bool)> = Vec::with_capacity(glyphs.len());
+    for glyph in glyphs {
+        clusters.push((glyph.info.cluster as usize, glyph.info.codepoint == 0));
+    }
+    clusters.sort_by_key(|&(cluster, _)| cluster);
+
+    // Merge the glyphs of each cluster, noting if any is missing.
+    let mut merged: Vec<(usize, bool)> = Vec::with_capacity(clusters.len());
+    for (cluster, missing) in clusters {
+        match merged.last_mut() {
+            Some(last) if last.0 == cluster => last.1 |= missing,
+            _ => merged.push((cluster, missing)),
+        }
+    }
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for (i, &(cluster, missing)) in merged.iter().enumerate() {
+        if !missing {
+            continue;
+        }
+        let next = merged.get(i + 1).map_or(end, |&(next, _)| next);
+        match ranges.last_mut() {
+            Some(last) if last.end == cluster => last.end = next,
+            _ => ranges.push(cluster..next),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FallbackShaper;
+    use {sys, Blob, Direction, Face, Font, Language, SegmentProperties};
+
+    static ROBOTO_ABC: &[u8] =
+        include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.abc.ttf");
+    static SOURCE_HAN: &[u8] = include_bytes!(
+        "../../harfbuzz-sys/harfbuzz/test/api/fonts/SourceHanSans-Regular.41,3041,4C2E.otf"
+    );
+
+    fn props(direction: Direction) -> SegmentProperties {
+        SegmentProperties {
+            direction,
+            script: sys::HB_SCRIPT_LATIN,
+            language: Language::from_string("en"),
+        }
+    }
+
+    fn summary(
+        shaper: &FallbackShaper,
+        text: &str,
+        direction: Direction,
+    ) -> Vec<(usize, u32, bool)> {
+        shaper
+            .shape(text, &props(direction), &[])
+            .iter()
+            .map(|g| (g.font_index, g.info.cluster, g.info.codepoint != 0))
+            .collect()
+    }
+
+    #[test]
+    fn test_fallback() {
+        let (latin, cjk) = (
+            Blob::new_read_only(ROBOTO_ABC),
+            Blob::new_read_only(SOURCE_HAN),
+        );
+        let (latin, cjk) = (Face::new(&latin, 0), Face::new(&cjk, 0));
+        let shaper = FallbackShaper::new(vec![Font::new(&latin), Font::new(&cjk)]);
+
+        // "ぁ" is three bytes, and "z" is in neither font.
+        assert_eq!(
+            summary(&shaper, "aぁぁbz", Direction::LTR),
+            vec![
+                (0, 0, true),
+                (1, 1, true),
+                (1, 4, true),
+                (0, 7, true),
+                (0, 8, false),
+            ]
+        );
+        assert_eq!(
+            summary(&shaper, "aぁb", Direction::RTL),
+            vec![(0, 4, true), (1, 1, true), (0, 0, true)]
+        );
+        assert_eq!(summary(&shaper, "", Direction::LTR), vec![]);
+
+        // Clusters that the fallback font doesn't cover either keep the
+        // primary font's glyphs, even when it covers others in the range.
+        let shaper = FallbackShaper::new(vec![Font::new(&cjk), Font::new(&latin)]);
+        assert_eq!(
+            summary(&shaper, "ぁzaぁ", Direction::LTR),
+            vec![(0, 0, true), (0, 3, false), (1, 4, true), (0, 5, true)]
+        );
+        assert_eq!(
+            summary(&shaper, "zaz", Direction::RTL),
+            vec![(0, 2, false), (1, 1, true), (0, 0, false)]
+        );
+    }
+}
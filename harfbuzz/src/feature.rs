@@ -0,0 +1,138 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std;
+use std::os::raw::{c_char, c_int, c_uint};
+use sys;
+
+use Tag;
+
+/// An OpenType feature setting to apply while shaping.
+///
+/// A feature applies a value to the feature identified by its tag, for
+/// the clusters in its range. For most features a value of `1` turns
+/// the feature on and `0` turns it off, while others such as `aalt`
+/// use the value to select an alternate.
+///
+/// ```
+/// # use harfbuzz::{Feature, Tag};
+/// let liga = Feature::from_string("-liga").unwrap();
+/// assert_eq!(liga, Feature::new(Tag::from_string("liga"), 0));
+/// assert_eq!(liga.to_string(), "-liga");
+///
+/// let salt = Feature::with_range(Tag::from_string("salt"), 2, 5, 10);
+/// assert_eq!(salt.to_string(), "salt[5:10]=2");
+/// ```
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+pub struct Feature(sys::hb_feature_t);
+
+impl Feature {
+    /// Create a feature that applies to the whole buffer.
+    pub fn new(tag: Tag, value: u32) -> Self {
+        Feature::with_range(tag, value, sys::HB_FEATURE_GLOBAL_START, c_uint::MAX)
+    }
+
+    /// Create a feature that applies to the clusters from `start` up to,
+    /// but not including, `end`.
+    pub fn with_range(tag: Tag, value: u32, start: u32, end: u32) -> Self {
+        Feature(sys::hb_feature_t {
+            tag: tag.into(),
+            value,
+            start,
+            end,
+        })
+    }
+
+    /// Parse a feature from the syntax accepted by `hb-shape`, such as
+    /// `kern`, `-liga`, `aalt=2` or `smcp[3:5]`.
+    ///
+    /// Returns `None` if the string could not be parsed.
+    pub fn from_string(feature: &str) -> Option<Self> {
+        let mut raw = Feature::new(Tag(0), 0).0;
+        let ok = unsafe {
+            sys::hb_feature_from_string(
+                feature.as_ptr() as *const c_char,
+                feature.len() as c_int,
+                &mut raw,
+            )
+        };
+        if ok != 0 {
+            Some(Feature(raw))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the tag of the feature.
+    pub fn tag(&self) -> Tag {
+        Tag(self.0.tag)
+    }
+
+    /// Returns the value the feature is set to.
+    pub fn value(&self) -> u32 {
+        self.0.value
+    }
+
+    /// Returns the first cluster the feature applies to.
+    pub fn start(&self) -> u32 {
+        self.0.start
+    }
+
+    /// Returns the cluster after the last one the feature applies to.
+    pub fn end(&self) -> u32 {
+        self.0.end
+    }
+
+    /// Returns a pointer to the underlying `hb_feature_t` values of a
+    /// slice of features.
+    pub(crate) fn slice_as_ptr(features: &[Feature]) -> *const sys::hb_feature_t {
+        features.as_ptr() as *const sys::hb_feature_t
+    }
+}
+
+impl From<sys::hb_feature_t> for Feature {
+    fn from(raw: sys::hb_feature_t) -> Self {
+        Feature(raw)
+    }
+}
+
+impl From<Feature> for sys::hb_feature_t {
+    fn from(feature: Feature) -> Self {
+        feature.0
+    }
+}
+
+impl PartialEq for Feature {
+    fn eq(&self, other: &Feature) -> bool {
+        self.0.tag == other.0.tag
+            && self.0.value == other.0.value
+            && self.0.start == other.0.start
+            && self.0.end == other.0.end
+    }
+}
+
+impl Eq for Feature {}
+
+impl std::fmt::Display for Feature {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut buf: [c_char; 128] = [0; 128];
+        let mut raw = self.0;
+        unsafe {
+            sys::hb_feature_to_string(&mut raw, buf.as_mut_ptr(), buf.len() as c_uint);
+            fmt.write_str(&std::ffi::CStr::from_ptr(buf.as_ptr()).to_string_lossy())
+        }
+    }
+}
+
+impl std::fmt::Debug for Feature {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "Feature({:?})", self.to_string())
+    }
+}
@@ -0,0 +1,582 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::mem::{self, size_of};
+use std::os::raw::{c_char, c_int, c_uint};
+use std::slice;
+use sys;
+
+use {Direction, Face};
+
+/// A font is a [`Face`] at a particular size, with a particular set of
+/// variation coordinates, from which glyph metrics are obtained.
+///
+/// Fonts created with [`Font::new`] use HarfBuzz's built-in OpenType
+/// font functions, and are initially scaled to the face's units per
+/// em.
+///
+/// ```
+/// # use harfbuzz::{Blob, Face, Font};
+/// let blob = Blob::new_read_only(&[]);
+/// let face = Face::new(&blob, 0);
+/// let mut font = Font::new(&face);
+/// font.set_scale(1024, 1024);
+/// assert_eq!(font.scale(), (1024, 1024));
+/// ```
+///
+/// [`Face`]: struct.Face.html
+/// [`Font::new`]: #method.new
+pub struct Font<'a> {
+    raw: *mut sys::hb_font_t,
+    phantom: PhantomData<&'a [u8]>,
+}
+
+impl<'a> Font<'a> {
+    /// Create a new font for `face`.
+    pub fn new(face: &Face<'a>) -> Font<'a> {
+        unsafe { Font::from_raw(sys::hb_font_create(face.as_raw())) }
+    }
+
+    /// Construct a `Font` from a raw pointer. Takes ownership of the font.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid `hb_font_t` that the caller owns a
+    /// reference to, which is released when the `Font` is dropped. The
+    /// data of the font's face must outlive `'a`.
+    pub unsafe fn from_raw(raw: *mut sys::hb_font_t) -> Self {
+        Font {
+            raw,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Borrows a raw pointer to the font.
+    pub fn as_raw(&self) -> *mut sys::hb_font_t {
+        self.raw
+    }
+
+    /// Gives up ownership and returns a raw pointer to the font.
+    pub fn into_raw(self) -> *mut sys::hb_font_t {
+        let raw = self.raw;
+        mem::forget(self);
+        raw
+    }
+
+    /// Returns the face this font was created from.
+    pub fn face(&self) -> Face<'a> {
+        unsafe { Face::from_raw(sys::hb_face_reference(sys::hb_font_get_face(self.raw))) }
+    }
+
+    /// Returns the horizontal and vertical scale of the font.
+    pub fn scale(&self) -> (i32, i32) {
+        let (mut x, mut y) = (0, 0);
+        unsafe { sys::hb_font_get_scale(self.raw, &mut x, &mut y) };
+        (x, y)
+    }
+
+    /// Sets the horizontal and vertical scale of the font.
+    ///
+    /// All metrics returned for this font are in units of the scale,
+    /// so a scale equal to the face's units per em gives metrics in
+    /// font units.
+    pub fn set_scale(&mut self, x: i32, y: i32) {
+        unsafe { sys::hb_font_set_scale(self.raw, x, y) };
+    }
+
+    /// Returns the horizontal and vertical pixels per em of the font.
+    pub fn ppem(&self) -> (u32, u32) {
+        let (mut x, mut y) = (0, 0);
+        unsafe { sys::hb_font_get_ppem(self.raw, &mut x, &mut y) };
+        (x, y)
+    }
+
+    /// Sets the horizontal and vertical pixels per em of the font.
+    ///
+    /// This is used to select hinting and bitmap strikes, and is zero
+    /// by default.
+    pub fn set_ppem(&mut self, x: u32, y: u32) {
+        unsafe { sys::hb_font_set_ppem(self.raw, x, y) };
+    }
+
+    /// Returns the point size of the font, or zero if unset.
+    pub fn ptem(&self) -> f32 {
+        unsafe { sys::hb_font_get_ptem(self.raw) }
+    }
+
+    /// Sets the point size of the font, used for optical sizing.
+    pub fn set_ptem(&mut self, ptem: f32) {
+        unsafe { sys::hb_font_set_ptem(self.raw, ptem) };
+    }
+
+    /// Returns the normalized variation coordinates of the font.
+    pub fn var_coords_normalized(&self) -> &[i32] {
+        unsafe {
+            let mut len = 0;
+            let ptr = sys::hb_font_get_var_coords_normalized(self.raw, &mut len);
+            if ptr.is_null() {
+                &[]
+            } else {
+                slice::from_raw_parts(ptr, len as usize)
+            }
+        }
+    }
+
+    /// Sets the normalized variation coordinates of the font, one for
+    /// each axis of the face, in 2.14 fixed point.
+    pub fn set_var_coords_normalized(&mut self, coords: &[i32]) {
+        unsafe {
+            sys::hb_font_set_var_coords_normalized(
+                self.raw,
+                coords.as_ptr(),
+                coords.len() as c_uint,
+            )
+        };
+    }
+
+    /// Returns the glyph the font's character map assigns to `c`, if
+    /// any.
+    pub fn nominal_glyph(&self, c: char) -> Option<u32> {
+        let mut glyph = 0;
+        if unsafe { sys::hb_font_get_nominal_glyph(self.raw, c as u32, &mut glyph) } != 0 {
+            Some(glyph)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the glyphs the font's character map assigns to each of
+    /// `chars`.
+    ///
+    /// The characters are looked up together, which is faster than
+    /// calling [`nominal_glyph`] for each of them.
+    ///
+    /// [`nominal_glyph`]: #method.nominal_glyph
+    pub fn nominal_glyphs(&self, chars: &[char]) -> Vec<Option<u32>> {
+        let mut glyphs = vec![0; chars.len()];
+        let mut result = Vec::with_capacity(chars.len());
+        let mut start = 0;
+        while start < chars.len() {
+            // HarfBuzz stops at the first character without a glyph, so
+            // skip it and carry on after it.
+            let done = unsafe {
+                sys::hb_font_get_nominal_glyphs(
+                    self.raw,
+                    (chars.len() - start) as c_uint,
+                    chars[start..].as_ptr() as *const sys::hb_codepoint_t,
+                    size_of::<char>() as c_uint,
+                    glyphs[start..].as_mut_ptr(),
+                    size_of::<sys::hb_codepoint_t>() as c_uint,
+                )
+            } as usize;
+            result.extend(glyphs[start..start + done].iter().map(|&glyph| Some(glyph)));
+            start += done;
+            if start < chars.len() {
+                result.push(None);
+                start += 1;
+            }
+        }
+        result
+    }
+
+    /// Returns the glyph for `c` followed by the variation selector
+    /// `selector`, if the font has a variant for that sequence.
+    pub fn variation_glyph(&self, c: char, selector: char) -> Option<u32> {
+        let mut glyph = 0;
+        if unsafe {
+            sys::hb_font_get_variation_glyph(self.raw, c as u32, selector as u32, &mut glyph)
+        } != 0
+        {
+            Some(glyph)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the name of `glyph`, if the font has glyph names.
+    pub fn glyph_name(&self, glyph: u32) -> Option<String> {
+        let mut name: [c_char; 128] = [0; 128];
+        unsafe {
+            if sys::hb_font_get_glyph_name(self.raw, glyph, name.as_mut_ptr(), name.len() as c_uint)
+                == 0
+            {
+                return None;
+            }
+            Some(CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned())
+        }
+    }
+
+    /// Returns the glyph called `name`, if any.
+    pub fn glyph_from_name(&self, name: &str) -> Option<u32> {
+        let mut glyph = 0;
+        if unsafe {
+            sys::hb_font_get_glyph_from_name(
+                self.raw,
+                name.as_ptr() as *const c_char,
+                name.len() as c_int,
+                &mut glyph,
+            )
+        } != 0
+        {
+            Some(glyph)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the horizontal advance of `glyph`.
+    pub fn glyph_h_advance(&self, glyph: u32) -> i32 {
+        unsafe { sys::hb_font_get_glyph_h_advance(self.raw, glyph) }
+    }
+
+    /// Returns the horizontal advances of `glyphs`.
+    ///
+    /// This is faster than calling [`glyph_h_advance`] for each glyph.
+    ///
+    /// [`glyph_h_advance`]: #method.glyph_h_advance
+    pub fn glyph_h_advances(&self, glyphs: &[u32]) -> Vec<i32> {
+        let mut advances = vec![0; glyphs.len()];
+        unsafe {
+            sys::hb_font_get_glyph_h_advances(
+                self.raw,
+                glyphs.len() as c_uint,
+                glyphs.as_ptr(),
+                size_of::<u32>() as c_uint,
+                advances.as_mut_ptr(),
+                size_of::<i32>() as c_uint,
+            )
+        };
+        advances
+    }
+
+    /// Returns the vertical advances of `glyphs`.
+    ///
+    /// This is faster than calling [`glyph_v_advance`] for each glyph.
+    ///
+    /// [`glyph_v_advance`]: #method.glyph_v_advance
+    pub fn glyph_v_advances(&self, glyphs: &[u32]) -> Vec<i32> {
+        let mut advances = vec![0; glyphs.len()];
+        unsafe {
+            sys::hb_font_get_glyph_v_advances(
+                self.raw,
+                glyphs.len() as c_uint,
+                glyphs.as_ptr(),
+                size_of::<u32>() as c_uint,
+                advances.as_mut_ptr(),
+                size_of::<i32>() as c_uint,
+            )
+        };
+        advances
+    }
+
+    /// Returns the extents of `glyph`, relative to its horizontal
+    /// origin, or `None` if they are not available.
+    ///
+    /// The y axis points up, so the height of a glyph with ink is
+    /// negative.
+    pub fn glyph_extents(&self, glyph: u32) -> Option<sys::hb_glyph_extents_t> {
+        unsafe {
+            let mut extents = mem::zeroed();
+            if sys::hb_font_get_glyph_extents(self.raw, glyph, &mut extents) != 0 {
+                Some(extents)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns the extents of `glyph`, relative to its origin for
+    /// text in `direction`, or `None` if they are not available.
+    pub fn glyph_extents_for_origin(
+        &self,
+        glyph: u32,
+        direction: Direction,
+    ) -> Option<sys::hb_glyph_extents_t> {
+        unsafe {
+            let mut extents = mem::zeroed();
+            if sys::hb_font_get_glyph_extents_for_origin(
+                self.raw,
+                glyph,
+                direction.into(),
+                &mut extents,
+            ) != 0
+            {
+                Some(extents)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns the position of the outline point `point_index` of
+    /// `glyph`, relative to its horizontal origin.
+    ///
+    /// HarfBuzz's built-in OpenType font functions do not load
+    /// outlines, so this is always `None` for fonts created with
+    /// [`Font::new`].
+    ///
+    /// [`Font::new`]: #method.new
+    pub fn glyph_contour_point(&self, glyph: u32, point_index: u32) -> Option<(i32, i32)> {
+        let (mut x, mut y) = (0, 0);
+        if unsafe {
+            sys::hb_font_get_glyph_contour_point(self.raw, glyph, point_index, &mut x, &mut y)
+        } != 0
+        {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the vertical font extents, or `None` if the font has no
+    /// vertical metrics.
+    ///
+    /// For vertical text the ascender is to the right of the baseline
+    /// and the descender to the left.
+    pub fn v_extents(&self) -> Option<sys::hb_font_extents_t> {
+        unsafe {
+            let mut extents = mem::zeroed();
+            if sys::hb_font_get_v_extents(self.raw, &mut extents) != 0 {
+                Some(extents)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns the vertical advance of `glyph`.
+    ///
+    /// The y axis points up, so advances for top to bottom text are
+    /// negative.
+    pub fn glyph_v_advance(&self, glyph: u32) -> i32 {
+        unsafe { sys::hb_font_get_glyph_v_advance(self.raw, glyph) }
+    }
+
+    /// Returns the vertical origin of `glyph`, relative to its
+    /// horizontal origin, or `None` if the font does not define one.
+    pub fn glyph_v_origin(&self, glyph: u32) -> Option<(i32, i32)> {
+        let (mut x, mut y) = (0, 0);
+        if unsafe { sys::hb_font_get_glyph_v_origin(self.raw, glyph, &mut x, &mut y) } != 0 {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the origin of `glyph` for text in `direction`, relative
+    /// to its horizontal origin.
+    ///
+    /// Glyph positions from shaping are relative to this origin, and
+    /// outlines and extents to the horizontal origin. If the font does
+    /// not define a vertical origin, one is synthesized from the
+    /// glyph's advance and the font's extents.
+    pub fn glyph_origin_for_direction(&self, glyph: u32, direction: Direction) -> (i32, i32) {
+        let (mut x, mut y) = (0, 0);
+        unsafe {
+            sys::hb_font_get_glyph_origin_for_direction(
+                self.raw,
+                glyph,
+                direction.into(),
+                &mut x,
+                &mut y,
+            )
+        };
+        (x, y)
+    }
+
+    /// Adds the origin of `glyph` for `direction` to `point`, turning
+    /// a position relative to that origin into one relative to the
+    /// horizontal origin.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Direction, Face, Font};
+    /// let blob = Blob::new_read_only(&[]);
+    /// let face = Face::new(&blob, 0);
+    /// let font = Font::new(&face);
+    /// let point = font.add_glyph_origin_for_direction(0, Direction::TTB, (10, 20));
+    /// assert_eq!(font.subtract_glyph_origin_for_direction(0, Direction::TTB, point), (10, 20));
+    /// ```
+    pub fn add_glyph_origin_for_direction(
+        &self,
+        glyph: u32,
+        direction: Direction,
+        point: (i32, i32),
+    ) -> (i32, i32) {
+        let (mut x, mut y) = point;
+        unsafe {
+            sys::hb_font_add_glyph_origin_for_direction(
+                self.raw,
+                glyph,
+                direction.into(),
+                &mut x,
+                &mut y,
+            )
+        };
+        (x, y)
+    }
+
+    /// Subtracts the origin of `glyph` for `direction` from `point`,
+    /// the inverse of [`add_glyph_origin_for_direction`].
+    ///
+    /// [`add_glyph_origin_for_direction`]: #method.add_glyph_origin_for_direction
+    pub fn subtract_glyph_origin_for_direction(
+        &self,
+        glyph: u32,
+        direction: Direction,
+        point: (i32, i32),
+    ) -> (i32, i32) {
+        let (mut x, mut y) = point;
+        unsafe {
+            sys::hb_font_subtract_glyph_origin_for_direction(
+                self.raw,
+                glyph,
+                direction.into(),
+                &mut x,
+                &mut y,
+            )
+        };
+        (x, y)
+    }
+}
+
+// HarfBuzz fonts are reference counted atomically, and are safe to use
+// from several threads as long as they are not modified.
+unsafe impl<'a> Send for Font<'a> {}
+unsafe impl<'a> Sync for Font<'a> {}
+
+impl<'a> Drop for Font<'a> {
+    /// Decrement the reference count, and destroy the font if the reference count is zero.
+    fn drop(&mut self) {
+        unsafe {
+            sys::hb_font_destroy(self.raw);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Font;
+    use {shape, sys, Blob, Buffer, Direction, Face, Language, SegmentProperties};
+
+    static ROBOTO: &[u8] =
+        include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.abc.ttf");
+    static CPAL: &[u8] = include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/cpal-v0.ttf");
+    static CMAP14: &[u8] =
+        include_bytes!("../../harfbuzz-sys/harfbuzz/test/subset/data/fonts/cmap14_font1.otf");
+
+    static SOURCE_HAN: &[u8] = include_bytes!(
+        "../../harfbuzz-sys/harfbuzz/test/api/fonts/SourceHanSans-Regular.41,3041,4C2E.otf"
+    );
+
+    #[test]
+    fn test_vertical() {
+        let blob = Blob::new_read_only(SOURCE_HAN);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        let extents = font.v_extents().unwrap();
+        assert_eq!((extents.ascender, extents.descender), (500, -500));
+
+        let mut buffer = Buffer::with("Aぁ");
+        buffer.set_segment_properties(&SegmentProperties {
+            direction: Direction::TTB,
+            script: sys::HB_SCRIPT_HIRAGANA,
+            language: Language::from_string("ja"),
+        });
+        shape(&font, &mut buffer, &[]);
+
+        let glyphs = buffer.glyph_infos().iter().zip(buffer.glyph_positions());
+        for (info, position) in glyphs {
+            let glyph = info.codepoint;
+            assert_eq!(position.x_advance, 0);
+            assert_eq!(position.y_advance, font.glyph_v_advance(glyph));
+            assert_eq!(font.glyph_v_advance(glyph), -1000);
+
+            // Shaped offsets are relative to the horizontal origin.
+            let origin = font.glyph_v_origin(glyph).unwrap();
+            assert_eq!(
+                font.glyph_origin_for_direction(glyph, Direction::TTB),
+                origin
+            );
+            assert_eq!(
+                font.add_glyph_origin_for_direction(
+                    glyph,
+                    Direction::TTB,
+                    (position.x_offset, position.y_offset)
+                ),
+                (0, 0)
+            );
+            assert_eq!(
+                font.glyph_origin_for_direction(glyph, Direction::LTR),
+                (0, 0)
+            );
+        }
+        assert_eq!(buffer.glyph_positions()[1].x_offset, -500);
+    }
+
+    #[test]
+    fn test_glyph_metrics() {
+        let blob = Blob::new_read_only(ROBOTO);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        let glyphs: Vec<u32> = font
+            .nominal_glyphs(&['a', 'b', 'c', 'z'])
+            .into_iter()
+            .map(|glyph| glyph.unwrap_or(0))
+            .collect();
+        assert_eq!(glyphs, vec![1, 2, 3, 0]);
+        assert_eq!(
+            font.nominal_glyphs(&['z', 'a', 'z', 'z', 'b']),
+            vec![None, Some(1), None, None, Some(2)]
+        );
+        assert!(font.nominal_glyphs(&[]).is_empty());
+        assert_eq!(
+            font.glyph_h_advances(&glyphs),
+            glyphs
+                .iter()
+                .map(|&glyph| font.glyph_h_advance(glyph))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            font.glyph_v_advances(&glyphs),
+            glyphs
+                .iter()
+                .map(|&glyph| font.glyph_v_advance(glyph))
+                .collect::<Vec<_>>()
+        );
+
+        let extents = font.glyph_extents(1).unwrap();
+        assert!(extents.width > 0 && extents.height < 0);
+        let for_origin = font.glyph_extents_for_origin(1, Direction::LTR).unwrap();
+        assert_eq!(for_origin.x_bearing, extents.x_bearing);
+        assert_eq!(font.glyph_contour_point(1, 0), None);
+
+        // This subset has no glyph names.
+        assert_eq!(font.glyph_name(1), None);
+        let blob = Blob::new_read_only(CPAL);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+        assert_eq!(font.glyph_name(1), Some("space".to_owned()));
+        assert_eq!(font.glyph_from_name("space"), Some(1));
+        assert_eq!(font.glyph_from_name("no such glyph"), None);
+    }
+
+    #[test]
+    fn test_variation_glyph() {
+        let blob = Blob::new_read_only(CMAP14);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        assert!(font.variation_glyph('\u{4E00}', '\u{E0100}').is_some());
+        assert_eq!(font.variation_glyph('\u{4E00}', '\u{FE00}'), None);
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ops::Range;
+use sys;
+
+#[cfg(feature = "unicode-bidi")]
+use unicode_bidi::{BidiInfo, Level};
+
+use {Direction, Language, SegmentProperties};
+
+/// A run of text that can be shaped with a single set of segment
+/// properties.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Item {
+    /// The byte range of the run in the itemized text.
+    pub range: Range<usize>,
+    /// The segment properties to shape the run with.
+    pub props: SegmentProperties,
+    /// The bidi embedding level of the run. Odd levels are right to
+    /// left.
+    pub level: u8,
+}
+
+/// Split `text` into runs of a single script.
+///
+/// Characters of the Common and Inherited scripts, such as spaces,
+/// punctuation and combining marks, take the script of the run they
+/// are in, or of the first run if they start the text. A closing
+/// bracket takes the script of its matching opening bracket.
+///
+/// The direction of each run is the horizontal direction of its
+/// script, so numbers and neutrals in right-to-left text are not
+/// reordered. Enable the `unicode-bidi` feature and use
+/// [`itemize_bidi`] for mixed-direction text.
+///
+/// Each item can be shaped with [`Buffer::add_str_item`] and
+/// [`Buffer::set_segment_properties`]:
+///
+/// ```
+/// # use harfbuzz::{itemize, sys, Buffer, Direction, Language};
+/// let text = "Hello, مرحبا!";
+/// let items = itemize(text, Language::from_string("en"));
+/// assert_eq!(items.len(), 2);
+/// assert_eq!(&text[items[0].range.clone()], "Hello, ");
+/// assert_eq!(items[0].props.script, sys::HB_SCRIPT_LATIN);
+/// assert_eq!(&text[items[1].range.clone()], "مرحبا!");
+/// assert_eq!(items[1].props.direction, Direction::RTL);
+///
+/// for item in &items {
+///     let mut buffer = Buffer::new();
+///     buffer.add_str_item(text, item.range.clone());
+///     buffer.set_segment_properties(&item.props);
+/// }
+/// ```
+///
+/// [`itemize_bidi`]: fn.itemize_bidi.html
+/// [`Buffer::add_str_item`]: struct.Buffer.html#method.add_str_item
+/// [`Buffer::set_segment_properties`]: struct.Buffer.html#method.set_segment_properties
+pub fn itemize(text: &str, language: Language) -> Vec<Item> {
+    script_runs(text)
+        .into_iter()
+        .map(|(range, script)| {
+            let direction = match unsafe { sys::hb_script_get_horizontal_direction(script) } {
+                sys::HB_DIRECTION_RTL => Direction::RTL,
+                _ => Direction::LTR,
+            };
+            Item {
+                range,
+                props: SegmentProperties {
+                    direction,
+                    script,
+                    language,
+                },
+                level: if direction == Direction::RTL { 1 } else { 0 },
+            }
+        })
+        .collect()
+}
+
+/// Split `text` into runs of a single script and bidi embedding level.
+///
+/// Scripts are resolved as for [`itemize`], and levels with the
+/// Unicode Bidirectional Algorithm. The direction of each run follows
+/// its level. If `base_direction` is `None`, the base direction of
+/// each paragraph is taken from its first strong character.
+///
+/// ```
+/// # use harfbuzz::{itemize_bidi, Direction, Language};
+/// let text = "abc אבג 123";
+/// let items = itemize_bidi(text, None, Language::from_string("he"));
+/// let runs: Vec<_> = items
+///     .iter()
+///     .map(|item| (&text[item.range.clone()], item.level))
+///     .collect();
+/// assert_eq!(runs, vec![("abc ", 0), ("אבג ", 1), ("123", 2)]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `base_direction` is not `LTR` or `RTL`.
+///
+/// [`itemize`]: fn.itemize.html
+#[cfg(feature = "unicode-bidi")]
+pub fn itemize_bidi(
+    text: &str,
+    base_direction: Option<Direction>,
+    language: Language,
+) -> Vec<Item> {
+    let base_level = base_direction.map(|direction| match direction {
+        Direction::LTR => Level::ltr(),
+        Direction::RTL => Level::rtl(),
+        _ => panic!("the base direction must be horizontal"),
+    });
+    let levels = BidiInfo::new(text, base_level).levels;
+
+    let mut items = Vec::new();
+    for (range, script) in script_runs(text) {
+        let mut start = range.start;
+        for (i, _) in text[range.clone()].char_indices() {
+            let i = range.start + i;
+            if levels[i] != levels[start] {
+                items.push(bidi_item(start..i, script, levels[start], language));
+                start = i;
+            }
+        }
+        items.push(bidi_item(start..range.end, script, levels[start], language));
+    }
+    items
+}
+
+#[cfg(feature = "unicode-bidi")]
+fn bidi_item(
+    range: Range<usize>,
+    script: sys::hb_script_t,
+    level: Level,
+    language: Language,
+) -> Item {
+    Item {
+        range,
+        props: SegmentProperties {
+            direction: if level.is_rtl() {
+                Direction::RTL
+            } else {
+                Direction::LTR
+            },
+            script,
+            language,
+        },
+        level: level.number(),
+    }
+}
+
+/// Returns `true` if characters of `script` take the script of the
+/// text around them.
+fn is_weak(script: sys::hb_script_t) -> bool {
+    script == sys::HB_SCRIPT_COMMON
+        || script == sys::HB_SCRIPT_INHERITED
+        || script == sys::HB_SCRIPT_UNKNOWN
+}
+
+/// Returns the byte ranges of the runs of a single script in `text`.
+fn script_runs(text: &str) -> Vec<(Range<usize>, sys::hb_script_t)> {
+    let funcs = unsafe { sys::hb_unicode_funcs_get_default() };
+    let mut runs = Vec::new();
+    // The closing bracket for each open bracket, and the script of the
+    // run it was opened in.
+    let mut brackets: Vec<(u32, sys::hb_script_t)> = Vec::new();
+    let mut start = 0;
+    let mut current = sys::HB_SCRIPT_COMMON;
+
+    for (i, c) in text.char_indices() {
+        let c = c as u32;
+        let mut script = unsafe { sys::hb_unicode_script(funcs, c) };
+        if is_weak(script) {
+            script = current;
+            match unsafe { sys::hb_unicode_general_category(funcs, c) } {
+                sys::HB_UNICODE_GENERAL_CATEGORY_OPEN_PUNCTUATION => {
+                    let close = unsafe { sys::hb_unicode_mirroring(funcs, c) };
+                    if close != c {
+                        brackets.push((close, current));
+                    }
+                }
+                sys::HB_UNICODE_GENERAL_CATEGORY_CLOSE_PUNCTUATION => {
+                    if let Some(index) = brackets.iter().rposition(|&(close, _)| close == c) {
+                        script = brackets[index].1;
+                        brackets.truncate(index);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if script != current {
+            if current == sys::HB_SCRIPT_COMMON {
+                // The text so far had no script of its own, so it
+                // joins this run, along with any brackets it opened.
+                for bracket in brackets.iter_mut() {
+                    bracket.1 = script;
+                }
+            } else {
+                runs.push((start..i, current));
+                start = i;
+            }
+            current = script;
+        }
+    }
+    if start < text.len() {
+        runs.push((start..text.len(), current));
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::itemize;
+    use {sys, Direction, Language};
+
+    fn runs(text: &str) -> Vec<(&str, sys::hb_script_t, Direction)> {
+        itemize(text, Language::from_string("en"))
+            .into_iter()
+            .map(|item| (&text[item.range], item.props.script, item.props.direction))
+            .collect()
+    }
+
+    #[test]
+    fn test_itemize() {
+        assert_eq!(runs(""), vec![]);
+        assert_eq!(
+            runs("123 "),
+            vec![("123 ", sys::HB_SCRIPT_COMMON, Direction::LTR)]
+        );
+        // Leading neutrals join the first run, and closing brackets
+        // the run of their opening bracket.
+        assert_eq!(
+            runs("«abc (αβγ) (абв»"),
+            vec![
+                ("«abc (", sys::HB_SCRIPT_LATIN, Direction::LTR),
+                ("αβγ", sys::HB_SCRIPT_GREEK, Direction::LTR),
+                (") (", sys::HB_SCRIPT_LATIN, Direction::LTR),
+                ("абв»", sys::HB_SCRIPT_CYRILLIC, Direction::LTR),
+            ]
+        );
+        // Combining marks stay with their base.
+        assert_eq!(
+            runs("e\u{301}שָׁלוֹם"),
+            vec![
+                ("e\u{301}", sys::HB_SCRIPT_LATIN, Direction::LTR),
+                ("שָׁלוֹם", sys::HB_SCRIPT_HEBREW, Direction::RTL),
+            ]
+        );
+    }
+}
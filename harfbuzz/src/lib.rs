@@ -19,6 +19,10 @@
 )]
 
 pub extern crate harfbuzz_sys as sys;
+#[cfg(feature = "unicode-bidi")]
+extern crate unicode_bidi;
+#[cfg(feature = "unicode-linebreak")]
+extern crate unicode_linebreak;
 
 mod buffer;
 pub use self::buffer::Buffer;
@@ -31,3 +35,57 @@ pub use self::language::Language;
 
 mod blob;
 pub use self::blob::Blob;
+
+mod tag;
+pub use self::tag::Tag;
+
+mod face;
+pub use self::face::Face;
+
+mod face_builder;
+pub use self::face_builder::FaceBuilder;
+
+mod font;
+pub use self::font::Font;
+
+mod feature;
+pub use self::feature::Feature;
+
+mod segment_properties;
+pub use self::segment_properties::SegmentProperties;
+
+mod shape_plan;
+pub use self::shape_plan::ShapePlan;
+
+mod shaper;
+pub use self::shaper::{available_shapers, Shaper};
+
+mod shape;
+pub use self::shape::{shape, shape_full};
+
+pub mod set;
+pub use self::set::Set;
+
+pub mod map;
+pub use self::map::Map;
+
+mod fallback;
+pub use self::fallback::{FallbackGlyph, FallbackShaper};
+
+mod itemize;
+#[cfg(feature = "unicode-bidi")]
+pub use self::itemize::itemize_bidi;
+pub use self::itemize::{itemize, Item};
+
+mod paragraph;
+pub use self::paragraph::{shape_paragraph, GlyphRun};
+
+#[cfg(feature = "unicode-linebreak")]
+mod line_break;
+#[cfg(feature = "unicode-linebreak")]
+pub use self::line_break::{break_lines, Line};
+
+mod caret;
+pub use self::caret::{caret_positions, hit_test, selection_ranges};
+
+pub mod ot;
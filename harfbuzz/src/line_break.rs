@@ -0,0 +1,319 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ops::Range;
+use sys;
+use unicode_linebreak::{linebreaks, BreakOpportunity};
+
+use paragraph::reorder;
+use {Direction, Feature, Font, GlyphRun, Item};
+
+/// A line of text produced by [`break_lines`].
+///
+/// [`break_lines`]: fn.break_lines.html
+#[derive(Clone, Debug)]
+pub struct Line {
+    /// The byte range of the line in the text, including any trailing
+    /// whitespace and line break.
+    pub range: Range<usize>,
+    /// The glyph runs of the line, in visual order.
+    pub runs: Vec<GlyphRun>,
+    /// The sum of the advances of the runs.
+    pub advance: i32,
+}
+
+/// Break shaped text into lines no wider than `max_width`.
+///
+/// `runs` are the glyph runs of `text` in any order, as returned by
+/// [`shape_paragraph`] with the same `font` and `features`. Lines end
+/// at the break opportunities of the Unicode Line Breaking Algorithm,
+/// and are filled greedily. Trailing whitespace does not count towards
+/// the width of a line, and a line with a single word wider than
+/// `max_width` overflows.
+///
+/// A run that is split between lines keeps its glyphs where it is safe
+/// to break, and only the text between the break and the nearest glyph
+/// that is safe to break at, according to
+/// `HB_GLYPH_FLAG_UNSAFE_TO_BREAK`, is shaped again, with only the text
+/// of its line as context. This gives the correct glyphs at the end and
+/// start of lines, for example breaking ligatures and giving Arabic
+/// letters their final or initial forms, without reshaping whole runs.
+///
+/// ```
+/// # use harfbuzz::{break_lines, shape_paragraph, Blob, Face, Font};
+/// let blob = Blob::new_read_only(&[]);
+/// let face = Face::new(&blob, 0);
+/// let font = Font::new(&face);
+///
+/// let text = "one two\nthree";
+/// let runs = shape_paragraph(text, &font, None, &[]);
+/// // Each glyph of the empty font is 1000 units wide.
+/// let lines = break_lines(text, &runs, &font, &[], 5000);
+/// let lines: Vec<_> = lines.iter().map(|line| &text[line.range.clone()]).collect();
+/// assert_eq!(lines, vec!["one ", "two\n", "three"]);
+/// ```
+///
+/// [`shape_paragraph`]: fn.shape_paragraph.html
+pub fn break_lines(
+    text: &str,
+    runs: &[GlyphRun],
+    font: &Font,
+    features: &[Feature],
+    max_width: i32,
+) -> Vec<Line> {
+    let mut logical: Vec<&GlyphRun> = runs.iter().collect();
+    logical.sort_by_key(|run| run.range.start);
+
+    // widths[i] is the advance of the clusters before byte offset i.
+    let mut widths = vec![0; text.len() + 1];
+    for run in &logical {
+        for (info, position) in run.infos.iter().zip(&run.positions) {
+            widths[info.cluster as usize + 1] += position.x_advance;
+        }
+    }
+    for i in 1..widths.len() {
+        widths[i] += widths[i - 1];
+    }
+    let measure = |range: Range<usize>| {
+        let end = range.start + text[range.clone()].trim_end().len();
+        widths[end] - widths[range.start]
+    };
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut candidate = None;
+    for (end, opportunity) in linebreaks(text) {
+        if let Some(candidate) = candidate {
+            if measure(start..end) > max_width {
+                ranges.push(start..candidate);
+                start = candidate;
+            }
+        }
+        match opportunity {
+            BreakOpportunity::Mandatory => {
+                if start < end {
+                    ranges.push(start..end);
+                    start = end;
+                }
+                candidate = None;
+            }
+            BreakOpportunity::Allowed => candidate = Some(end),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|range| {
+            let mut runs: Vec<GlyphRun> = logical
+                .iter()
+                .filter(|run| run.range.start < range.end && range.start < run.range.end)
+                .map(|run| {
+                    let start = run.range.start.max(range.start);
+                    let end = run.range.end.min(range.end);
+                    if start == run.range.start && end == run.range.end {
+                        (*run).clone()
+                    } else {
+                        slice(text, range.clone(), run, start..end, font, features)
+                    }
+                })
+                .collect();
+            reorder(&mut runs, |run| run.level);
+            Line {
+                advance: runs.iter().map(|run| run.advance).sum(),
+                range,
+                runs,
+            }
+        })
+        .collect()
+}
+
+/// Returns `true` if `run` can be split before byte offset `pos`
+/// without shaping it again.
+fn is_safe_to_break(run: &GlyphRun, pos: usize) -> bool {
+    if pos == run.range.start || pos == run.range.end {
+        return true;
+    }
+    match run.infos.iter().find(|info| info.cluster as usize == pos) {
+        Some(info) => {
+            let flags = unsafe { sys::hb_glyph_info_get_glyph_flags(info) };
+            flags & sys::HB_GLYPH_FLAG_UNSAFE_TO_BREAK == 0
+        }
+        // The offset is inside a cluster.
+        None => false,
+    }
+}
+
+/// Returns the part of `run` for the text in `range`, shaping only the
+/// text between the ends of `range` and the nearest offsets that are
+/// safe to break at, with the text in `line` as context.
+fn slice(
+    text: &str,
+    line: Range<usize>,
+    run: &GlyphRun,
+    range: Range<usize>,
+    font: &Font,
+    features: &[Feature],
+) -> GlyphRun {
+    // The end of the run is always safe, and so is the start.
+    let head_end = (range.start..=run.range.end)
+        .find(|&pos| is_safe_to_break(run, pos))
+        .unwrap()
+        .min(range.end);
+    let tail_start = (run.range.start..=range.end)
+        .rev()
+        .find(|&pos| is_safe_to_break(run, pos))
+        .unwrap()
+        .max(head_end);
+
+    let reshape = |range: Range<usize>| {
+        if range.start == range.end {
+            return (Vec::new(), Vec::new());
+        }
+        // Shape the text of the line alone, so that nothing on other
+        // lines affects the glyphs at its ends, then make the clusters
+        // offsets into the whole text again.
+        let item = Item {
+            range: range.start - line.start..range.end - line.start,
+            props: run.props,
+            level: run.level,
+        };
+        let mut shaped = GlyphRun::shape(&text[line.clone()], item, font, features);
+        for info in &mut shaped.infos {
+            info.cluster += line.start as u32;
+        }
+        (shaped.infos, shaped.positions)
+    };
+    let kept = run
+        .infos
+        .iter()
+        .zip(&run.positions)
+        .filter(|&(info, _)| {
+            let cluster = info.cluster as usize;
+            head_end <= cluster && cluster < tail_start
+        })
+        .map(|(&info, &position)| (info, position))
+        .unzip();
+
+    let mut parts = vec![
+        reshape(range.start..head_end),
+        kept,
+        reshape(tail_start..range.end),
+    ];
+    if run.props.direction == Direction::RTL {
+        parts.reverse();
+    }
+    let mut infos = Vec::new();
+    let mut positions = Vec::new();
+    for (part_infos, part_positions) in parts {
+        infos.extend(part_infos);
+        positions.extend(part_positions);
+    }
+    GlyphRun {
+        range,
+        props: run.props,
+        level: run.level,
+        advance: positions.iter().map(|position| position.x_advance).sum(),
+        infos,
+        positions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{break_lines, is_safe_to_break, slice};
+    use {shape_paragraph, Blob, Direction, Face, Font, GlyphRun};
+
+    static ROBOTO_ABC: &[u8] =
+        include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.abc.ttf");
+    static ROBOTO_FI: &[u8] =
+        include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.gsub.fi.ttf");
+    static ARABIC: &[u8] = include_bytes!(
+        "../../harfbuzz-sys/harfbuzz/test/shaping/data/in-house/fonts/24b8d24d00ae86f49791b746da4c9d3f717a51a8.ttf"
+    );
+
+    #[test]
+    fn test_break_lines() {
+        let blob = Blob::new_read_only(ROBOTO_ABC);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        let first = shape_paragraph("abc cab", &font, Some(Direction::LTR), &[]);
+        let width = first.iter().map(|run| run.advance).sum();
+
+        let text = "abc cab bca\nc";
+        let runs = shape_paragraph(text, &font, Some(Direction::LTR), &[]);
+        let lines = break_lines(text, &runs, &font, &[], width);
+        let ranges: Vec<_> = lines.iter().map(|line| line.range.clone()).collect();
+        assert_eq!(ranges, vec![0..8, 8..12, 12..13]);
+
+        // The first line is split from the run of the whole text, and is
+        // the same as the text of the line shaped alone.
+        let direct = shape_paragraph("abc cab ", &font, Some(Direction::LTR), &[]);
+        assert_eq!(lines[0].runs.len(), 1);
+        assert_eq!(lines[0].advance, direct[0].advance);
+        assert!(lines[0].advance > width);
+        let glyphs = |run: &GlyphRun| -> Vec<(u32, u32)> {
+            run.infos
+                .iter()
+                .map(|info| (info.codepoint, info.cluster))
+                .collect()
+        };
+        assert_eq!(glyphs(&lines[0].runs[0]), glyphs(&direct[0]));
+
+        // A word wider than the line overflows.
+        let lines = break_lines(text, &runs, &font, &[], 0);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0].range, 0..4);
+
+        assert!(break_lines("", &[], &font, &[], 0).is_empty());
+    }
+
+    #[test]
+    fn test_unsafe_to_break() {
+        let blob = Blob::new_read_only(ROBOTO_FI);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        let text = "fi";
+        let runs = shape_paragraph(text, &font, Some(Direction::LTR), &[]);
+        assert_eq!(runs[0].infos.len(), 1);
+
+        // Breaking inside the ligature shapes each side again.
+        let head = slice(text, 0..1, &runs[0], 0..1, &font, &[]);
+        let tail = slice(text, 1..2, &runs[0], 1..2, &font, &[]);
+        assert_eq!(head.infos.len(), 1);
+        assert_eq!(tail.infos.len(), 1);
+        assert_eq!(tail.infos[0].cluster, 1);
+        assert_ne!(head.infos[0].codepoint, runs[0].infos[0].codepoint);
+        assert_eq!(head.advance, head.positions[0].x_advance);
+    }
+
+    #[test]
+    fn test_arabic_joining() {
+        let blob = Blob::new_read_only(ARABIC);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        // Two behs: a final form, then an initial form, in visual order.
+        let text = "\u{628}\u{628}";
+        let runs = shape_paragraph(text, &font, None, &[]);
+        let isolated = shape_paragraph("\u{628}", &font, None, &[])[0].infos[0].codepoint;
+        let glyphs: Vec<u32> = runs[0].infos.iter().map(|info| info.codepoint).collect();
+        assert!(!glyphs.contains(&isolated));
+
+        // Split between lines, each letter stands alone, which takes
+        // shaping both of them again.
+        assert!(!is_safe_to_break(&runs[0], 2));
+        let head = slice(text, 0..2, &runs[0], 0..2, &font, &[]);
+        let tail = slice(text, 2..4, &runs[0], 2..4, &font, &[]);
+        assert_eq!(head.infos[0].codepoint, isolated);
+        assert_eq!(tail.infos[0].codepoint, isolated);
+        assert_eq!(tail.infos[0].cluster, 2);
+    }
+}
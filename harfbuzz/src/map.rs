@@ -0,0 +1,302 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A map between integers, and iterators over its entries.
+
+use std;
+use std::iter::FromIterator;
+use sys;
+
+use set;
+use Set;
+
+/// The value HarfBuzz uses for missing keys and values.
+const INVALID: u32 = u32::MAX;
+
+/// A hash map from `u32` keys to `u32` values, such as a mapping from
+/// old to new glyph indices.
+///
+/// The value `u32::MAX` is reserved, and can be neither a key nor a
+/// value.
+///
+/// ```
+/// # use harfbuzz::Map;
+/// let mut map: Map = vec![(1, 10), (2, 20)].into_iter().collect();
+/// assert_eq!(map.insert(3, 30), None);
+/// assert_eq!(map.insert(1, 11), Some(10));
+/// assert_eq!(map.remove(2), Some(20));
+/// assert_eq!(map.get(1), Some(11));
+/// assert_eq!(map.len(), 2);
+/// let entries: Vec<_> = map.iter().collect();
+/// assert_eq!(entries, vec![(1, 11), (3, 30)]);
+/// ```
+///
+/// ## Iteration
+///
+/// HarfBuzz provides no way to enumerate the keys of a map, so `Map`
+/// keeps track of the keys inserted through it. For the same reason, a
+/// map filled in by HarfBuzz, such as by the subsetter, is adopted with
+/// [`from_raw_in`] given a set of candidate keys:
+///
+/// ```
+/// # use harfbuzz::{Map, Set, sys};
+/// let glyphs: Set = (0..100).collect();
+/// let map = unsafe {
+///     let raw = sys::hb_map_create();
+///     sys::hb_map_set(raw, 5, 50);
+///     sys::hb_map_set(raw, 500, 5);
+///     Map::from_raw_in(raw, &glyphs)
+/// };
+/// assert_eq!(map.len(), 1);
+/// assert_eq!(map.iter().collect::<Vec<_>>(), vec![(5, 50)]);
+/// ```
+///
+/// Entries added through [`as_raw`] are not known to the `Map`.
+///
+/// [`from_raw_in`]: #method.from_raw_in
+/// [`as_raw`]: #method.as_raw
+pub struct Map {
+    raw: *mut sys::hb_map_t,
+    keys: Set,
+}
+
+impl Map {
+    /// Create a new, empty map.
+    pub fn new() -> Self {
+        Map::default()
+    }
+
+    /// Construct a `Map` from the entries of a raw map whose keys are in
+    /// `keys`. Takes ownership of the raw map, and destroys it.
+    ///
+    /// The entries are copied to a new map, so that its keys are known
+    /// and entries with other keys are left out.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid `hb_map_t` that the caller owns a reference
+    /// to.
+    pub unsafe fn from_raw_in(raw: *mut sys::hb_map_t, keys: &Set) -> Self {
+        let mut map = Map::new();
+        for key in keys {
+            if sys::hb_map_has(raw, key) != 0 {
+                map.insert(key, sys::hb_map_get(raw, key));
+            }
+        }
+        sys::hb_map_destroy(raw);
+        map
+    }
+
+    /// Borrows a raw pointer to the map.
+    pub fn as_raw(&self) -> *mut sys::hb_map_t {
+        self.raw
+    }
+
+    /// Gives up ownership and returns a raw pointer to the map.
+    pub fn into_raw(self) -> *mut sys::hb_map_t {
+        let raw = self.raw;
+        std::mem::forget(self);
+        raw
+    }
+
+    /// Returns `false` if any memory allocation for the map has failed.
+    pub fn allocation_successful(&self) -> bool {
+        unsafe { sys::hb_map_allocation_successful(self.raw) != 0 }
+    }
+
+    /// Remove all entries from the map.
+    pub fn clear(&mut self) {
+        unsafe { sys::hb_map_clear(self.raw) };
+        self.keys.clear();
+    }
+
+    /// Returns `true` if the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        // hb_map_get_population() miscounts when entries are replaced
+        // or deleted, so count the keys instead.
+        self.keys.len()
+    }
+
+    /// Returns the value for `key`, if any.
+    pub fn get(&self, key: u32) -> Option<u32> {
+        match unsafe { sys::hb_map_get(self.raw, key) } {
+            INVALID => None,
+            value => Some(value),
+        }
+    }
+
+    /// Returns `true` if the map has a value for `key`.
+    pub fn contains_key(&self, key: u32) -> bool {
+        unsafe { sys::hb_map_has(self.raw, key) != 0 }
+    }
+
+    /// Set the value for `key`, returning the previous value if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` or `value` is `u32::MAX`.
+    pub fn insert(&mut self, key: u32, value: u32) -> Option<u32> {
+        assert!(key != INVALID && value != INVALID, "u32::MAX is reserved");
+        let old = self.get(key);
+        unsafe { sys::hb_map_set(self.raw, key, value) };
+        self.keys.insert(key);
+        old
+    }
+
+    /// Remove the entry for `key`, returning its value if any.
+    pub fn remove(&mut self, key: u32) -> Option<u32> {
+        let old = self.get(key);
+        unsafe { sys::hb_map_del(self.raw, key) };
+        self.keys.remove(key);
+        old
+    }
+
+    /// Returns an iterator over the entries of the map, in ascending
+    /// order of key.
+    pub fn iter(&self) -> Iter<'_> {
+        self.iter_in(&self.keys)
+    }
+
+    /// Returns an iterator over the entries whose keys are in `keys`,
+    /// in ascending order of key.
+    pub fn iter_in<'a>(&'a self, keys: &'a Set) -> Iter<'a> {
+        Iter {
+            map: self,
+            keys: keys.iter(),
+        }
+    }
+}
+
+/// An iterator over the entries of a [`Map`].
+///
+/// [`Map`]: struct.Map.html
+pub struct Iter<'a> {
+    map: &'a Map,
+    keys: set::Iter<'a>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<(u32, u32)> {
+        let map = self.map;
+        self.keys
+            .by_ref()
+            .filter_map(|key| map.get(key).map(|value| (key, value)))
+            .next()
+    }
+}
+
+impl<'a> IntoIterator for &'a Map {
+    type Item = (u32, u32);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl Extend<(u32, u32)> for Map {
+    fn extend<I: IntoIterator<Item = (u32, u32)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl FromIterator<(u32, u32)> for Map {
+    fn from_iter<I: IntoIterator<Item = (u32, u32)>>(iter: I) -> Self {
+        let mut map = Map::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl std::fmt::Debug for Map {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl Default for Map {
+    /// Create a new, empty map.
+    fn default() -> Self {
+        Map {
+            raw: unsafe { sys::hb_map_create() },
+            keys: Set::new(),
+        }
+    }
+}
+
+unsafe impl Send for Map {}
+
+impl Drop for Map {
+    fn drop(&mut self) {
+        unsafe { sys::hb_map_destroy(self.raw) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Map;
+    use {sys, Set};
+
+    #[test]
+    fn test_map() {
+        let mut map: Map = (0..1000).map(|k| (k, k * 2)).collect();
+        assert_eq!(map.len(), 1000);
+        assert!(map.allocation_successful());
+        assert_eq!(map.get(999), Some(1998));
+        assert_eq!(map.get(1000), None);
+        assert!(map.contains_key(0));
+
+        for k in (0..1000).filter(|k| k % 3 != 0) {
+            assert_eq!(map.remove(k), Some(k * 2));
+        }
+        assert_eq!(map.remove(1), None);
+        assert_eq!(map.len(), 334);
+        assert!(map.iter().all(|(k, v)| k % 3 == 0 && v == k * 2));
+        assert_eq!(map.iter().count(), 334);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.iter().next(), None);
+        assert_eq!(format!("{:?}", map), "{}");
+    }
+
+    #[test]
+    fn test_adopted_map() {
+        let keys: Set = (0..10).collect();
+        let mut map = unsafe {
+            let raw = sys::hb_map_create();
+            sys::hb_map_set(raw, 7, 70);
+            sys::hb_map_set(raw, 3, 30);
+            sys::hb_map_del(raw, 3);
+            sys::hb_map_set(raw, 20, 200);
+            Map::from_raw_in(raw, &keys)
+        };
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(20), None);
+        assert_eq!(format!("{:?}", map), "{7: 70}");
+
+        assert_eq!(map.remove(7), Some(70));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "reserved")]
+    fn test_reserved_value() {
+        Map::new().insert(1, u32::MAX);
+    }
+}
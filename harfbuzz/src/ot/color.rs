@@ -0,0 +1,384 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The color tables of a font: `CPAL` and `COLR`, which hold its color
+//! palettes and the colored layers its glyphs are drawn from, and
+//! `SVG `, `sbix` and `CBDT`, which hold color glyph images.
+//!
+//! ```
+//! # use harfbuzz::{Blob, Face};
+//! let blob = Blob::new_read_only(&[]);
+//! let face = Face::new(&blob, 0);
+//! assert!(!face.has_palettes());
+//! assert!(face.palettes().is_empty());
+//! assert!(!face.has_color_layers());
+//! assert!(face.color_layers(0).is_empty());
+//! assert!(face.glyph_svg(0).is_none());
+//! ```
+
+use sys;
+
+use super::collect_array;
+use super::name::NameId;
+use {Blob, Face, Font};
+
+/// The palette index of a layer drawn in the foreground color of the
+/// text rather than a palette color.
+pub const FOREGROUND_COLOR_INDEX: u32 = 0xFFFF;
+
+/// A color with 8-bit red, green, blue and alpha channels.
+///
+/// The channels are not premultiplied by alpha. It can be converted to
+/// or from `hb_color_t` using the `From` and `Into` traits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    /// The red channel.
+    pub red: u8,
+    /// The green channel.
+    pub green: u8,
+    /// The blue channel.
+    pub blue: u8,
+    /// The alpha channel, from transparent at 0 to opaque at 255.
+    pub alpha: u8,
+}
+
+impl Color {
+    /// Creates a color from its channels.
+    pub fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Color {
+        Color {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+}
+
+impl From<sys::hb_color_t> for Color {
+    fn from(color: sys::hb_color_t) -> Self {
+        unsafe {
+            Color {
+                red: sys::hb_color_get_red(color),
+                green: sys::hb_color_get_green(color),
+                blue: sys::hb_color_get_blue(color),
+                alpha: sys::hb_color_get_alpha(color),
+            }
+        }
+    }
+}
+
+impl From<Color> for sys::hb_color_t {
+    fn from(color: Color) -> Self {
+        u32::from(color.blue) << 24
+            | u32::from(color.green) << 16
+            | u32::from(color.red) << 8
+            | u32::from(color.alpha)
+    }
+}
+
+/// A color palette of a face, as returned by [`Face::palette`].
+///
+/// [`Face::palette`]: ../../struct.Face.html#method.palette
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette {
+    /// The index of the palette in the face.
+    pub index: u32,
+    /// The colors of the palette, indexed by the palette indices of
+    /// color layers.
+    pub colors: Vec<Color>,
+    /// Whether the palette is meant for use on a light background.
+    pub usable_with_light_background: bool,
+    /// Whether the palette is meant for use on a dark background.
+    pub usable_with_dark_background: bool,
+    /// The identifier of the palette's name, which can be looked up with
+    /// [`Face::name`].
+    ///
+    /// [`Face::name`]: ../../struct.Face.html#method.name
+    pub name_id: Option<NameId>,
+}
+
+/// Converts a name identifier to `None` if it refers to no name.
+fn name_id(id: sys::hb_ot_name_id_t) -> Option<NameId> {
+    if id == sys::HB_OT_NAME_ID_INVALID {
+        None
+    } else {
+        Some(id.into())
+    }
+}
+
+impl<'a> Face<'a> {
+    /// Returns `true` if the face has color palettes in a `CPAL` table.
+    pub fn has_palettes(&self) -> bool {
+        unsafe { sys::hb_ot_color_has_palettes(self.as_raw()) != 0 }
+    }
+
+    /// Returns the number of color palettes in the face.
+    pub fn palette_count(&self) -> u32 {
+        unsafe { sys::hb_ot_color_palette_get_count(self.as_raw()) }
+    }
+
+    /// Returns the color palette at `index`, or `None` if the face has
+    /// no such palette.
+    ///
+    /// Palette 0 is the default one.
+    pub fn palette(&self, index: u32) -> Option<Palette> {
+        if index >= self.palette_count() {
+            return None;
+        }
+        unsafe {
+            let face = self.as_raw();
+            let colors: Vec<sys::hb_color_t> = collect_array(|start, count, colors| {
+                sys::hb_ot_color_palette_get_colors(face, index, start, count, colors)
+            });
+            let flags = sys::hb_ot_color_palette_get_flags(face, index);
+            Some(Palette {
+                index,
+                colors: colors.into_iter().map(Color::from).collect(),
+                usable_with_light_background: flags
+                    & sys::HB_OT_COLOR_PALETTE_FLAG_USABLE_WITH_LIGHT_BACKGROUND
+                    != 0,
+                usable_with_dark_background: flags
+                    & sys::HB_OT_COLOR_PALETTE_FLAG_USABLE_WITH_DARK_BACKGROUND
+                    != 0,
+                name_id: name_id(sys::hb_ot_color_palette_get_name_id(face, index)),
+            })
+        }
+    }
+
+    /// Returns the color palettes of the face.
+    pub fn palettes(&self) -> Vec<Palette> {
+        (0..self.palette_count())
+            .filter_map(|index| self.palette(index))
+            .collect()
+    }
+
+    /// Returns the identifier of the name of the color at `color_index`
+    /// in each palette, which can be looked up with [`Face::name`].
+    ///
+    /// [`Face::name`]: struct.Face.html#method.name
+    pub fn palette_color_name_id(&self, color_index: u32) -> Option<NameId> {
+        name_id(unsafe { sys::hb_ot_color_palette_color_get_name_id(self.as_raw(), color_index) })
+    }
+
+    /// Returns `true` if the face has colored glyph layers in a `COLR`
+    /// table.
+    pub fn has_color_layers(&self) -> bool {
+        unsafe { sys::hb_ot_color_has_layers(self.as_raw()) != 0 }
+    }
+
+    /// Returns the layers `glyph` is drawn from, bottom first, as pairs
+    /// of a glyph and the index of its color in a palette.
+    ///
+    /// A color index of [`FOREGROUND_COLOR_INDEX`] means the layer is
+    /// drawn in the foreground color of the text. The list is empty if
+    /// the glyph has no color layers.
+    ///
+    /// [`FOREGROUND_COLOR_INDEX`]: ot/color/constant.FOREGROUND_COLOR_INDEX.html
+    pub fn color_layers(&self, glyph: u32) -> Vec<(u32, u32)> {
+        let layers: Vec<sys::hb_ot_color_layer_t> = unsafe {
+            collect_array(|start, count, layers| {
+                sys::hb_ot_color_glyph_get_layers(self.as_raw(), glyph, start, count, layers)
+            })
+        };
+        layers
+            .into_iter()
+            .map(|layer| (layer.glyph, layer.color_index))
+            .collect()
+    }
+
+    /// Returns `true` if the face has SVG glyph images in an `SVG `
+    /// table.
+    pub fn has_svg_glyphs(&self) -> bool {
+        unsafe { sys::hb_ot_color_has_svg(self.as_raw()) != 0 }
+    }
+
+    /// Returns `true` if the face has PNG glyph images in an `sbix` or
+    /// `CBDT` table, which can be read with [`Font::glyph_png`].
+    ///
+    /// [`Font::glyph_png`]: struct.Font.html#method.glyph_png
+    pub fn has_png_glyphs(&self) -> bool {
+        unsafe { sys::hb_ot_color_has_png(self.as_raw()) != 0 }
+    }
+
+    /// Returns the SVG document holding the image of `glyph`, or `None`
+    /// if the glyph has none.
+    ///
+    /// A document can hold the images of several glyphs, each in the
+    /// element with the id `glyph` followed by the glyph index, and can
+    /// be compressed with gzip.
+    pub fn glyph_svg(&self, glyph: u32) -> Option<Blob<'a>> {
+        non_empty(unsafe {
+            Blob::from_raw(sys::hb_ot_color_glyph_reference_svg(self.as_raw(), glyph))
+        })
+    }
+}
+
+impl<'a> Font<'a> {
+    /// Returns the PNG image of `glyph`, or `None` if the glyph has
+    /// none.
+    ///
+    /// The image is taken from the bitmap strike whose size is closest
+    /// to, and not below, the font's ppem, or from the largest strike if
+    /// the ppem is not set or larger than all strikes.
+    pub fn glyph_png(&self, glyph: u32) -> Option<Blob<'a>> {
+        non_empty(unsafe {
+            Blob::from_raw(sys::hb_ot_color_glyph_reference_png(self.as_raw(), glyph))
+        })
+    }
+}
+
+/// Converts an empty blob, which HarfBuzz returns for missing images, to
+/// `None`.
+fn non_empty(blob: Blob) -> Option<Blob> {
+    if blob.is_empty() {
+        None
+    } else {
+        Some(blob)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Color;
+    use ot::name::NameId;
+    use {sys, Blob, Face, FaceBuilder, Font, Tag};
+
+    static CPAL_V0: &[u8] =
+        include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/cpal-v0.ttf");
+    static CPAL_V1: &[u8] =
+        include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/cpal-v1.ttf");
+    static SVG: &[u8] =
+        include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/chromacheck-svg.ttf");
+    static SBIX: &[u8] =
+        include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/chromacheck-sbix.ttf");
+    static CBDT: &[u8] =
+        include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/chromacheck-cbdt.ttf");
+
+    #[test]
+    fn test_palettes() {
+        let blob = Blob::new_read_only(CPAL_V0);
+        let face = Face::new(&blob, 0);
+        assert!(face.has_palettes());
+        let palettes = face.palettes();
+        assert_eq!(palettes.len(), 2);
+        assert_eq!(
+            palettes[0].colors,
+            vec![
+                Color::new(0x00, 0x00, 0x00, 0xff),
+                Color::new(0x66, 0xcc, 0xff, 0xff)
+            ]
+        );
+        assert_eq!(palettes[1].colors[1], Color::new(0x80, 0x00, 0x00, 0xff));
+        assert!(!palettes[0].usable_with_light_background);
+        assert_eq!(palettes[0].name_id, None);
+        assert_eq!(face.palette(2), None);
+
+        let blob = Blob::new_read_only(CPAL_V1);
+        let face = Face::new(&blob, 0);
+        let palettes = face.palettes();
+        assert_eq!(palettes.len(), 3);
+        assert!(palettes[0].usable_with_dark_background);
+        assert!(!palettes[0].usable_with_light_background);
+        assert!(palettes[1].usable_with_light_background);
+        assert_eq!(palettes[0].name_id, Some(NameId::Other(257)));
+        assert_eq!(palettes[1].name_id, None);
+        assert_eq!(palettes[2].name_id, Some(NameId::Other(258)));
+        assert_eq!(palettes[1].colors[1], Color::new(0xff, 0xcc, 0x66, 0xff));
+        assert_eq!(face.palette_color_name_id(1), Some(NameId::Other(256)));
+        assert_eq!(face.palette_color_name_id(0), None);
+
+        let color = Color::new(0x12, 0x34, 0x56, 0x78);
+        assert_eq!(Color::from(sys::hb_color_t::from(color)), color);
+    }
+
+    #[test]
+    fn test_color_layers() {
+        let blob = Blob::new_read_only(CPAL_V1);
+        let face = Face::new(&blob, 0);
+        assert!(face.has_color_layers());
+        assert_eq!(face.color_layers(2), vec![(3, 1), (4, 0)]);
+        assert!(face.color_layers(1).is_empty());
+        assert!(!face.has_svg_glyphs());
+        assert!(!face.has_png_glyphs());
+    }
+
+    #[test]
+    fn test_glyph_images() {
+        let blob = Blob::new_read_only(SVG);
+        let face = Face::new(&blob, 0);
+        assert!(face.has_svg_glyphs());
+        assert!(face.glyph_svg(0).is_none());
+        let svg = face.glyph_svg(1).unwrap();
+        assert_eq!(svg.len(), 146);
+        assert!(svg.starts_with(b"<?xml"));
+
+        for &(data, len) in &[(SBIX, 224), (CBDT, 88)] {
+            let blob = Blob::new_read_only(data);
+            let face = Face::new(&blob, 0);
+            assert!(face.has_png_glyphs());
+            let mut font = Font::new(&face);
+            assert!(font.glyph_png(0).is_none());
+            assert_eq!(&font.glyph_png(1).unwrap()[1..4], b"PNG");
+            assert_eq!(font.glyph_png(1).unwrap().len(), len);
+            font.set_ppem(12, 12);
+            assert!(font.glyph_png(1).is_some());
+        }
+    }
+
+    #[test]
+    fn test_png_strikes() {
+        let blob = Blob::new_read_only(SBIX);
+        let face = Face::new(&blob, 0);
+        let original = Font::new(&face).glyph_png(1).unwrap().to_vec();
+
+        // Add a 20 ppem copy of the font's only strike, with a different
+        // last byte in the image of glyph 1.
+        let tag = Tag::from_string("sbix");
+        let sbix = face.table(tag).unwrap();
+        let read = |data: &[u8], pos: usize| {
+            u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize
+        };
+        let strike = &sbix[read(&sbix, 8)..];
+        let mut small = strike.to_vec();
+        small[..2].copy_from_slice(&20u16.to_be_bytes());
+        let end = read(&small, 4 + 4 * 2);
+        small[end - 1] ^= 0xff;
+        let mut table = sbix[..4].to_vec();
+        for &value in &[2, 16, 16 + strike.len()] {
+            table.extend_from_slice(&(value as u32).to_be_bytes());
+        }
+        table.extend_from_slice(strike);
+        table.extend_from_slice(&small);
+
+        let mut builder = FaceBuilder::new();
+        for other in face.table_tags() {
+            if other != tag {
+                builder.add_table(other, face.table(other).unwrap());
+            }
+        }
+        builder.add_table(tag, Blob::new_read_only(&table));
+        let face = builder.build();
+        let mut font = Font::new(&face);
+
+        // The smallest strike at least as large as the ppem is used, or
+        // the largest one.
+        for &(ppem, expected_small) in &[
+            (0, false),
+            (12, true),
+            (20, true),
+            (21, false),
+            (400, false),
+        ] {
+            font.set_ppem(ppem, ppem);
+            let png = font.glyph_png(1).unwrap();
+            assert_eq!(png.len(), original.len());
+            assert_eq!(png[..] != original[..], expected_small, "ppem {}", ppem);
+        }
+    }
+}
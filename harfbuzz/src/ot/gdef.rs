@@ -0,0 +1,172 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `GDEF` table, which classifies glyphs and holds their attachment
+//! points and ligature carets.
+//!
+//! ```
+//! # use harfbuzz::{Blob, Face};
+//! # use harfbuzz::ot::gdef::GlyphClass;
+//! let blob = Blob::new_read_only(&[]);
+//! let face = Face::new(&blob, 0);
+//! assert!(!face.has_glyph_classes());
+//! assert_eq!(face.glyph_class(0), GlyphClass::Unclassified);
+//! assert!(face.glyphs_in_class(GlyphClass::Mark).is_empty());
+//! ```
+
+use sys;
+
+use super::collect_array;
+use {Direction, Face, Font, Set};
+
+/// The class of a glyph in the `GDEF` table.
+///
+/// It can be converted to or from `hb_ot_layout_glyph_class_t` using
+/// the `From` and `Into` traits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GlyphClass {
+    /// A glyph with no class.
+    Unclassified,
+    /// A spacing glyph for a single character, which can take marks.
+    Base,
+    /// A glyph for several characters.
+    Ligature,
+    /// A non-spacing combining glyph.
+    Mark,
+    /// A spacing glyph for part of a single character.
+    Component,
+}
+
+impl From<sys::hb_ot_layout_glyph_class_t> for GlyphClass {
+    fn from(class: sys::hb_ot_layout_glyph_class_t) -> Self {
+        match class {
+            sys::HB_OT_LAYOUT_GLYPH_CLASS_BASE_GLYPH => GlyphClass::Base,
+            sys::HB_OT_LAYOUT_GLYPH_CLASS_LIGATURE => GlyphClass::Ligature,
+            sys::HB_OT_LAYOUT_GLYPH_CLASS_MARK => GlyphClass::Mark,
+            sys::HB_OT_LAYOUT_GLYPH_CLASS_COMPONENT => GlyphClass::Component,
+            _ => GlyphClass::Unclassified,
+        }
+    }
+}
+
+impl From<GlyphClass> for sys::hb_ot_layout_glyph_class_t {
+    fn from(class: GlyphClass) -> Self {
+        match class {
+            GlyphClass::Unclassified => sys::HB_OT_LAYOUT_GLYPH_CLASS_UNCLASSIFIED,
+            GlyphClass::Base => sys::HB_OT_LAYOUT_GLYPH_CLASS_BASE_GLYPH,
+            GlyphClass::Ligature => sys::HB_OT_LAYOUT_GLYPH_CLASS_LIGATURE,
+            GlyphClass::Mark => sys::HB_OT_LAYOUT_GLYPH_CLASS_MARK,
+            GlyphClass::Component => sys::HB_OT_LAYOUT_GLYPH_CLASS_COMPONENT,
+        }
+    }
+}
+
+impl<'a> Face<'a> {
+    /// Returns `true` if the face classifies its glyphs in a `GDEF`
+    /// table.
+    pub fn has_glyph_classes(&self) -> bool {
+        unsafe { sys::hb_ot_layout_has_glyph_classes(self.as_raw()) != 0 }
+    }
+
+    /// Returns the class of `glyph`.
+    pub fn glyph_class(&self, glyph: u32) -> GlyphClass {
+        unsafe { sys::hb_ot_layout_get_glyph_class(self.as_raw(), glyph).into() }
+    }
+
+    /// Returns the glyphs in `class`.
+    ///
+    /// For `GlyphClass::Unclassified`, only the glyphs that the table
+    /// explicitly leaves unclassified are returned, not those missing
+    /// from it.
+    pub fn glyphs_in_class(&self, class: GlyphClass) -> Set {
+        let set = Set::new();
+        unsafe { sys::hb_ot_layout_get_glyphs_in_class(self.as_raw(), class.into(), set.as_raw()) };
+        set
+    }
+
+    /// Returns the indices of the contour points of `glyph` that marks
+    /// can attach to, as listed in the `GDEF` table.
+    pub fn attach_points(&self, glyph: u32) -> Vec<u32> {
+        unsafe {
+            collect_array(|start, count, points| {
+                sys::hb_ot_layout_get_attach_points(self.as_raw(), glyph, start, count, points)
+            })
+        }
+    }
+}
+
+impl<'a> Font<'a> {
+    /// Returns the caret positions inside the ligature `glyph` for text
+    /// in `direction`, from the font's `GDEF` or `lcar` table, scaled by
+    /// the font. Positions are along the x axis, from left to right, for
+    /// horizontal directions, and along the y axis for vertical ones.
+    ///
+    /// The list is empty if the font has no carets for the glyph.
+    pub fn ligature_carets(&self, direction: Direction, glyph: u32) -> Vec<i32> {
+        unsafe {
+            collect_array(|start, count, carets| {
+                sys::hb_ot_layout_get_ligature_carets(
+                    self.as_raw(),
+                    direction.into(),
+                    glyph,
+                    start,
+                    count,
+                    carets,
+                )
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GlyphClass;
+    use {sys, Blob, Direction, Face, Font};
+
+    static MARKS: &[u8] = include_bytes!(
+        "../../../harfbuzz-sys/harfbuzz/test/shaping/data/in-house/fonts/85fe0be440c64ac77699e21c2f1bd933a919167e.ttf"
+    );
+    static LCAR: &[u8] = include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/lcar.ttf");
+
+    #[test]
+    fn test_glyph_classes() {
+        let blob = Blob::new_read_only(MARKS);
+        let face = Face::new(&blob, 0);
+        assert!(face.has_glyph_classes());
+        assert_eq!(face.glyph_class(1), GlyphClass::Base);
+        assert_eq!(face.glyph_class(2), GlyphClass::Mark);
+        assert_eq!(face.glyph_class(0), GlyphClass::Unclassified);
+        let marks: Vec<u32> = face.glyphs_in_class(GlyphClass::Mark).iter().collect();
+        assert_eq!(marks, vec![2, 3]);
+        assert!(face.glyphs_in_class(GlyphClass::Ligature).is_empty());
+
+        assert_eq!(face.attach_points(1), vec![0, 45]);
+        assert_eq!(face.attach_points(2), vec![13]);
+        assert!(face.attach_points(0).is_empty());
+
+        for class in 0..6 {
+            let raw: sys::hb_ot_layout_glyph_class_t = GlyphClass::from(class).into();
+            assert_eq!(raw, if class < 5 { class } else { 0 });
+        }
+    }
+
+    #[test]
+    fn test_ligature_carets() {
+        let blob = Blob::new_read_only(LCAR);
+        let face = Face::new(&blob, 0);
+        let mut font = Font::new(&face);
+        assert_eq!(font.ligature_carets(Direction::LTR, 98), vec![565, 1172]);
+        assert!(font.ligature_carets(Direction::LTR, 1).is_empty());
+
+        let upem = face.upem() as i32;
+        font.set_scale(upem * 2, upem * 4);
+        assert_eq!(font.ligature_carets(Direction::RTL, 98), vec![1130, 2344]);
+        assert_eq!(font.ligature_carets(Direction::BTT, 98), vec![2260, 4688]);
+    }
+}
@@ -0,0 +1,932 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `GSUB` and `GPOS` tables, which hold the substitution and
+//! positioning lookups of a font, organized by script, language system
+//! and feature.
+//!
+//! ```
+//! # use harfbuzz::{Blob, Face, Tag};
+//! let blob = Blob::new_read_only(&[]);
+//! let face = Face::new(&blob, 0);
+//! for script in face.gsub().scripts() {
+//!     for language in script.languages() {
+//!         for feature in language.features() {
+//!             println!("{} {} {}: {:?}", script.tag(), language.tag(), feature.tag(), feature.lookups());
+//!         }
+//!     }
+//! }
+//! assert!(face.gsub().find_script(Tag::from_string("latn")).is_none());
+//! ```
+
+use std::os::raw::c_uint;
+use std::ptr;
+use sys;
+
+use super::collect_array;
+use super::name::NameId;
+use {Buffer, Direction, Face, Feature, Font, Language, Set, Tag};
+
+/// The tag of the `GSUB` table.
+pub const GSUB: Tag = Tag(0x4753_5542);
+
+/// The tag of the `GPOS` table.
+pub const GPOS: Tag = Tag(0x4750_4f53);
+
+/// The tag of the default language system of a script.
+pub const DEFAULT_LANGUAGE: Tag = Tag(0x6466_6c74);
+
+/// The `GSUB` or `GPOS` table of a face.
+#[derive(Copy, Clone)]
+pub struct LayoutTable<'a> {
+    face: &'a Face<'a>,
+    tag: Tag,
+}
+
+impl<'a> Face<'a> {
+    /// Returns the face's `GSUB` table, which holds its glyph
+    /// substitutions.
+    ///
+    /// The table is empty if the face has none.
+    pub fn gsub(&self) -> LayoutTable<'_> {
+        LayoutTable {
+            face: self,
+            tag: GSUB,
+        }
+    }
+
+    /// Returns the face's `GPOS` table, which holds its glyph
+    /// positioning.
+    ///
+    /// The table is empty if the face has none.
+    pub fn gpos(&self) -> LayoutTable<'_> {
+        LayoutTable {
+            face: self,
+            tag: GPOS,
+        }
+    }
+}
+
+impl<'a> LayoutTable<'a> {
+    /// The tag of the table, either [`GSUB`] or [`GPOS`].
+    ///
+    /// [`GSUB`]: constant.GSUB.html
+    /// [`GPOS`]: constant.GPOS.html
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// Returns the scripts of the table, in the order they appear in it.
+    pub fn scripts(&self) -> Vec<Script<'a>> {
+        let tags = unsafe {
+            collect_array(|start, count, tags| {
+                sys::hb_ot_layout_table_get_script_tags(
+                    self.face.as_raw(),
+                    self.tag.0,
+                    start,
+                    count,
+                    tags,
+                )
+            })
+        };
+        tags.into_iter()
+            .enumerate()
+            .map(|(index, tag)| Script {
+                table: *self,
+                index: index as u32,
+                tag: Tag(tag),
+            })
+            .collect()
+    }
+
+    /// Returns the script with the given tag, if the table has it.
+    pub fn find_script(&self, tag: Tag) -> Option<Script<'a>> {
+        let mut index = 0;
+        let found = unsafe {
+            sys::hb_ot_layout_table_find_script(self.face.as_raw(), self.tag.0, tag.0, &mut index)
+        };
+        if found != 0 {
+            Some(Script {
+                table: *self,
+                index,
+                tag,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns all the features of the table, in the order they appear
+    /// in it, so that the index of each feature is its position.
+    ///
+    /// A tag can appear several times, for features used by different
+    /// language systems.
+    pub fn features(&self) -> Vec<LayoutFeature<'a>> {
+        self.feature_tags()
+            .into_iter()
+            .enumerate()
+            .map(|(index, tag)| LayoutFeature {
+                table: *self,
+                index: index as u32,
+                tag,
+            })
+            .collect()
+    }
+
+    /// Returns the feature at `index` in the table, if there is one.
+    pub fn feature(&self, index: u32) -> Option<LayoutFeature<'a>> {
+        let mut count = 1;
+        let mut tag = 0;
+        unsafe {
+            sys::hb_ot_layout_table_get_feature_tags(
+                self.face.as_raw(),
+                self.tag.0,
+                index,
+                &mut count,
+                &mut tag,
+            )
+        };
+        if count == 1 {
+            Some(LayoutFeature {
+                table: *self,
+                index,
+                tag: Tag(tag),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the tags of all the features of the table.
+    fn feature_tags(&self) -> Vec<Tag> {
+        let tags = unsafe {
+            collect_array(|start, count, tags| {
+                sys::hb_ot_layout_table_get_feature_tags(
+                    self.face.as_raw(),
+                    self.tag.0,
+                    start,
+                    count,
+                    tags,
+                )
+            })
+        };
+        tags.into_iter().map(Tag).collect()
+    }
+
+    /// Returns the number of lookups in the table.
+    pub fn lookup_count(&self) -> u32 {
+        unsafe { sys::hb_ot_layout_table_get_lookup_count(self.face.as_raw(), self.tag.0) }
+    }
+
+    /// Returns the indices of the features used by the given scripts,
+    /// language systems and features.
+    ///
+    /// `None` stands for all scripts, language systems or features.
+    /// Default language systems are only included when `languages` is
+    /// `None`, as they cannot be selected by tag.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Face, Tag};
+    /// let blob = Blob::new_read_only(&[]);
+    /// let face = Face::new(&blob, 0);
+    /// let liga = [Tag::from_string("liga")];
+    /// assert!(face.gsub().collect_features(None, None, Some(&liga)).is_empty());
+    /// ```
+    pub fn collect_features(
+        &self,
+        scripts: Option<&[Tag]>,
+        languages: Option<&[Tag]>,
+        features: Option<&[Tag]>,
+    ) -> Set {
+        self.collect(
+            scripts,
+            languages,
+            features,
+            sys::hb_ot_layout_collect_features,
+        )
+    }
+
+    /// Returns the indices of the lookups used by the given scripts,
+    /// language systems and features.
+    ///
+    /// `None` stands for all scripts, language systems or features, as
+    /// for [`collect_features`].
+    ///
+    /// [`collect_features`]: #method.collect_features
+    pub fn collect_lookups(
+        &self,
+        scripts: Option<&[Tag]>,
+        languages: Option<&[Tag]>,
+        features: Option<&[Tag]>,
+    ) -> Set {
+        self.collect(
+            scripts,
+            languages,
+            features,
+            sys::hb_ot_layout_collect_lookups,
+        )
+    }
+
+    fn collect(
+        &self,
+        scripts: Option<&[Tag]>,
+        languages: Option<&[Tag]>,
+        features: Option<&[Tag]>,
+        f: unsafe extern "C" fn(
+            *mut sys::hb_face_t,
+            sys::hb_tag_t,
+            *const sys::hb_tag_t,
+            *const sys::hb_tag_t,
+            *const sys::hb_tag_t,
+            *mut sys::hb_set_t,
+        ),
+    ) -> Set {
+        // HarfBuzz takes arrays terminated by a zero tag, or null for all.
+        let terminated = |tags: Option<&[Tag]>| {
+            tags.map(|tags| {
+                let mut raw: Vec<sys::hb_tag_t> = tags.iter().map(|tag| tag.0).collect();
+                raw.push(0);
+                raw
+            })
+        };
+        let as_ptr =
+            |raw: &Option<Vec<sys::hb_tag_t>>| raw.as_ref().map_or(ptr::null(), |raw| raw.as_ptr());
+        let (scripts, languages, features) = (
+            terminated(scripts),
+            terminated(languages),
+            terminated(features),
+        );
+        let set = Set::new();
+        unsafe {
+            f(
+                self.face.as_raw(),
+                self.tag.0,
+                as_ptr(&scripts),
+                as_ptr(&languages),
+                as_ptr(&features),
+                set.as_raw(),
+            );
+        }
+        set
+    }
+}
+
+/// A script in a [`LayoutTable`].
+///
+/// [`LayoutTable`]: struct.LayoutTable.html
+#[derive(Copy, Clone)]
+pub struct Script<'a> {
+    table: LayoutTable<'a>,
+    index: u32,
+    tag: Tag,
+}
+
+impl<'a> Script<'a> {
+    /// The OpenType tag of the script, such as `latn`.
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// The index of the script in its table.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the language systems of the script, starting with its
+    /// default language system if it has one.
+    pub fn languages(&self) -> Vec<LanguageSystem<'a>> {
+        let tags = unsafe {
+            collect_array(|start, count, tags| {
+                sys::hb_ot_layout_script_get_language_tags(
+                    self.table.face.as_raw(),
+                    self.table.tag.0,
+                    self.index,
+                    start,
+                    count,
+                    tags,
+                )
+            })
+        };
+        let mut languages: Vec<_> = self.default_language().into_iter().collect();
+        languages.extend(
+            tags.into_iter()
+                .enumerate()
+                .map(|(index, tag)| LanguageSystem {
+                    script: *self,
+                    index: index as u32,
+                    tag: Tag(tag),
+                }),
+        );
+        languages
+    }
+
+    /// Returns the default language system of the script, which is used
+    /// for languages the script has no language system for, if the
+    /// script has one.
+    ///
+    /// Its tag is [`DEFAULT_LANGUAGE`]. A default language system without
+    /// any features is treated as missing, since HarfBuzz cannot tell the
+    /// two apart.
+    ///
+    /// [`DEFAULT_LANGUAGE`]: constant.DEFAULT_LANGUAGE.html
+    pub fn default_language(&self) -> Option<LanguageSystem<'a>> {
+        if self.has_default_language() {
+            Some(LanguageSystem {
+                script: *self,
+                index: sys::HB_OT_LAYOUT_DEFAULT_LANGUAGE_INDEX,
+                tag: DEFAULT_LANGUAGE,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the script has a default language system.
+    ///
+    /// HarfBuzz treats a missing default language system as an empty
+    /// one, so a default language system without any features counts as
+    /// missing.
+    fn has_default_language(&self) -> bool {
+        let face = self.table.face.as_raw();
+        let language = sys::HB_OT_LAYOUT_DEFAULT_LANGUAGE_INDEX;
+        let mut count = 0;
+        unsafe {
+            sys::hb_ot_layout_language_get_required_feature_index(
+                face,
+                self.table.tag.0,
+                self.index,
+                language,
+                ptr::null_mut(),
+            ) != 0
+                || sys::hb_ot_layout_language_get_feature_indexes(
+                    face,
+                    self.table.tag.0,
+                    self.index,
+                    language,
+                    0,
+                    &mut count,
+                    ptr::null_mut(),
+                ) != 0
+        }
+    }
+
+    /// Returns the language system with the given OpenType tag, such as
+    /// `TRK `, if the script has it.
+    pub fn find_language(&self, tag: Tag) -> Option<LanguageSystem<'a>> {
+        if tag == DEFAULT_LANGUAGE {
+            return self.default_language();
+        }
+        let mut index = 0;
+        let found = unsafe {
+            sys::hb_ot_layout_script_select_language(
+                self.table.face.as_raw(),
+                self.table.tag.0,
+                self.index,
+                1,
+                &tag.0,
+                &mut index,
+            )
+        };
+        if found != 0 {
+            Some(LanguageSystem {
+                script: *self,
+                index,
+                tag,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// A language system of a [`Script`], which selects the features used
+/// for a language written in that script.
+///
+/// [`Script`]: struct.Script.html
+#[derive(Copy, Clone)]
+pub struct LanguageSystem<'a> {
+    script: Script<'a>,
+    index: u32,
+    tag: Tag,
+}
+
+impl<'a> LanguageSystem<'a> {
+    /// The OpenType tag of the language system, or
+    /// [`DEFAULT_LANGUAGE`] for the default one.
+    ///
+    /// [`DEFAULT_LANGUAGE`]: constant.DEFAULT_LANGUAGE.html
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// The index of the language system in its script, or
+    /// `HB_OT_LAYOUT_DEFAULT_LANGUAGE_INDEX` for the default one.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns `true` if this is the default language system of its
+    /// script.
+    pub fn is_default(&self) -> bool {
+        self.index == sys::HB_OT_LAYOUT_DEFAULT_LANGUAGE_INDEX
+    }
+
+    /// The script the language system belongs to.
+    pub fn script(&self) -> Script<'a> {
+        self.script
+    }
+
+    /// Returns the feature that is always applied for this language
+    /// system, if it has one.
+    pub fn required_feature(&self) -> Option<LayoutFeature<'a>> {
+        let mut index = 0;
+        let mut tag = 0;
+        let found = unsafe {
+            sys::hb_ot_layout_language_get_required_feature(
+                self.script.table.face.as_raw(),
+                self.script.table.tag.0,
+                self.script.index,
+                self.index,
+                &mut index,
+                &mut tag,
+            )
+        };
+        if found != 0 {
+            Some(LayoutFeature {
+                table: self.script.table,
+                index,
+                tag: Tag(tag),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the features of the language system, not including its
+    /// required feature.
+    pub fn features(&self) -> Vec<LayoutFeature<'a>> {
+        let table = self.script.table;
+        let indices: Vec<c_uint> = unsafe {
+            collect_array(|start, count, indices| {
+                sys::hb_ot_layout_language_get_feature_indexes(
+                    table.face.as_raw(),
+                    table.tag.0,
+                    self.script.index,
+                    self.index,
+                    start,
+                    count,
+                    indices,
+                )
+            })
+        };
+        let tags: Vec<sys::hb_tag_t> = unsafe {
+            collect_array(|start, count, tags| {
+                sys::hb_ot_layout_language_get_feature_tags(
+                    table.face.as_raw(),
+                    table.tag.0,
+                    self.script.index,
+                    self.index,
+                    start,
+                    count,
+                    tags,
+                )
+            })
+        };
+        indices
+            .into_iter()
+            .zip(tags)
+            .map(|(index, tag)| LayoutFeature {
+                table,
+                index,
+                tag: Tag(tag),
+            })
+            .collect()
+    }
+
+    /// Returns the feature of the language system with the given tag, if
+    /// it has one.
+    pub fn find_feature(&self, tag: Tag) -> Option<LayoutFeature<'a>> {
+        let mut index = 0;
+        let found = unsafe {
+            sys::hb_ot_layout_language_find_feature(
+                self.script.table.face.as_raw(),
+                self.script.table.tag.0,
+                self.script.index,
+                self.index,
+                tag.0,
+                &mut index,
+            )
+        };
+        if found != 0 {
+            Some(LayoutFeature {
+                table: self.script.table,
+                index,
+                tag,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// A feature in a [`LayoutTable`], such as `liga` or `kern`.
+///
+/// [`LayoutTable`]: struct.LayoutTable.html
+#[derive(Copy, Clone)]
+pub struct LayoutFeature<'a> {
+    table: LayoutTable<'a>,
+    index: u32,
+    tag: Tag,
+}
+
+impl<'a> LayoutFeature<'a> {
+    /// The tag of the feature.
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// The index of the feature in its table.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the indices of the lookups of the feature, in the order
+    /// they are listed in it.
+    pub fn lookups(&self) -> Vec<u32> {
+        unsafe {
+            collect_array(|start, count, lookups| {
+                sys::hb_ot_layout_feature_get_lookups(
+                    self.table.face.as_raw(),
+                    self.table.tag.0,
+                    self.index,
+                    start,
+                    count,
+                    lookups,
+                )
+            })
+        }
+    }
+
+    /// Returns the names and characters a user interface can show for
+    /// the feature, in `language`, if it is a stylistic set (`ss01` to
+    /// `ss20`) or character variant (`cv01` to `cv99`) that has them.
+    ///
+    /// This is the same as [`Face::feature_ui_info`].
+    ///
+    /// [`Face::feature_ui_info`]: ../../struct.Face.html#method.feature_ui_info
+    pub fn ui_info(&self, language: Option<Language>) -> Option<FeatureUiInfo> {
+        self.table
+            .face
+            .feature_ui_info(self.table.tag, self.index, language)
+    }
+}
+
+/// The names and characters of a stylistic set or character variant
+/// feature, for showing it in a user interface.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeatureUiInfo {
+    /// The name of the feature, such as "Alternate g".
+    pub label: Option<String>,
+    /// A description of the feature. Only character variants have one.
+    pub tooltip: Option<String>,
+    /// Sample text showing the effect of the feature. Only character
+    /// variants have one.
+    pub sample: Option<String>,
+    /// The names of the alternates a character variant selects between,
+    /// for feature values from 1 on.
+    pub param_labels: Vec<Option<String>>,
+    /// The characters a character variant affects.
+    pub characters: Vec<char>,
+}
+
+impl<'a> Face<'a> {
+    /// Returns the names and characters of the feature at
+    /// `feature_index` in `table`, which is [`GSUB`] or [`GPOS`], as
+    /// found in the feature's parameters.
+    ///
+    /// Only stylistic sets (`ss01` to `ss20`) and character variants
+    /// (`cv01` to `cv99`) have such parameters, and `None` is returned
+    /// for other features. Names are looked up in exactly `language`, or
+    /// in English if it is `None`, as for [`Face::name`].
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Face};
+    /// # use harfbuzz::ot::layout::GSUB;
+    /// let blob = Blob::new_read_only(&[]);
+    /// let face = Face::new(&blob, 0);
+    /// assert_eq!(face.feature_ui_info(GSUB, 0, None), None);
+    /// ```
+    ///
+    /// [`GSUB`]: ot/layout/constant.GSUB.html
+    /// [`GPOS`]: ot/layout/constant.GPOS.html
+    /// [`Face::name`]: #method.name
+    pub fn feature_ui_info(
+        &self,
+        table: Tag,
+        feature_index: u32,
+        language: Option<Language>,
+    ) -> Option<FeatureUiInfo> {
+        let mut label = 0;
+        let mut tooltip = 0;
+        let mut sample = 0;
+        let mut param_count = 0;
+        let mut first_param = 0;
+        let found = unsafe {
+            sys::hb_ot_layout_feature_get_name_ids(
+                self.as_raw(),
+                table.0,
+                feature_index,
+                &mut label,
+                &mut tooltip,
+                &mut sample,
+                &mut param_count,
+                &mut first_param,
+            )
+        };
+        if found == 0 {
+            return None;
+        }
+
+        // Zero means that there is no name.
+        let name = |id: sys::hb_ot_name_id_t| {
+            if id == 0 || id == sys::HB_OT_NAME_ID_INVALID {
+                None
+            } else {
+                self.name(NameId::from(id), language)
+            }
+        };
+        let characters: Vec<sys::hb_codepoint_t> = unsafe {
+            collect_array(|start, count, characters| {
+                sys::hb_ot_layout_feature_get_characters(
+                    self.as_raw(),
+                    table.0,
+                    feature_index,
+                    start,
+                    count,
+                    characters,
+                )
+            })
+        };
+        Some(FeatureUiInfo {
+            label: name(label),
+            tooltip: name(tooltip),
+            sample: name(sample),
+            param_labels: (0..param_count).map(|i| name(first_param + i)).collect(),
+            characters: characters
+                .into_iter()
+                .filter_map(std::char::from_u32)
+                .collect(),
+        })
+    }
+
+    /// Returns `true` if the `GSUB` lookup at index `lookup` would
+    /// substitute the sequence `glyphs`.
+    ///
+    /// If `zero_context` is `true`, lookups that need context before or
+    /// after the sequence do not apply.
+    pub fn would_substitute(&self, lookup: u32, glyphs: &[u32], zero_context: bool) -> bool {
+        unsafe {
+            sys::hb_ot_layout_lookup_would_substitute(
+                self.as_raw(),
+                lookup,
+                glyphs.as_ptr(),
+                glyphs.len() as c_uint,
+                zero_context as sys::hb_bool_t,
+            ) != 0
+        }
+    }
+
+    /// Add to `glyphs` every glyph the `GSUB` lookup at index `lookup`
+    /// could substitute them with, recursively.
+    pub fn lookup_substitute_closure(&self, lookup: u32, glyphs: &mut Set) {
+        unsafe {
+            sys::hb_ot_layout_lookup_substitute_closure(self.as_raw(), lookup, glyphs.as_raw())
+        }
+    }
+
+    /// Add to `glyphs` every glyph the `GSUB` lookups in `lookups` could
+    /// substitute them with, recursively.
+    pub fn lookups_substitute_closure(&self, lookups: &Set, glyphs: &mut Set) {
+        unsafe {
+            sys::hb_ot_layout_lookups_substitute_closure(
+                self.as_raw(),
+                lookups.as_raw(),
+                glyphs.as_raw(),
+            )
+        }
+    }
+}
+
+impl<'a> Font<'a> {
+    /// Returns every glyph that shaping the text of `buffer` with
+    /// `features` could produce, using the font's `GSUB` lookups for the
+    /// buffer's segment properties.
+    ///
+    /// Characters the font has no glyph for are skipped. The result is a
+    /// superset of the glyphs that shaping gives, as lookups are applied
+    /// regardless of context, which makes it suitable for subsetting.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Buffer, Face, Font};
+    /// let blob = Blob::new_read_only(&[]);
+    /// let face = Face::new(&blob, 0);
+    /// let font = Font::new(&face);
+    /// let mut buffer = Buffer::with("Hello");
+    /// buffer.guess_segment_properties();
+    /// // The empty font has no glyphs for the text.
+    /// assert!(font.glyphs_closure(&buffer, &[]).is_empty());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the direction of `buffer` is not set, or if `buffer`
+    /// has already been shaped.
+    pub fn glyphs_closure(&self, buffer: &Buffer, features: &[Feature]) -> Set {
+        assert!(
+            buffer.get_direction() != Direction::Invalid,
+            "buffer direction is not set"
+        );
+        unsafe {
+            assert_eq!(
+                sys::hb_buffer_get_content_type(buffer.as_ptr()),
+                sys::HB_BUFFER_CONTENT_TYPE_UNICODE,
+                "buffer has already been shaped"
+            );
+        }
+        let glyphs = Set::new();
+        unsafe {
+            sys::hb_ot_shape_glyphs_closure(
+                self.as_raw(),
+                buffer.as_ptr(),
+                Feature::slice_as_ptr(features),
+                features.len() as c_uint,
+                glyphs.as_raw(),
+            )
+        };
+        glyphs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DEFAULT_LANGUAGE, GPOS, GSUB};
+    use ot::name::NameId;
+    use {Blob, Buffer, Face, Feature, Font, Language, Set, Tag};
+
+    static ROBOTO_FIL: &[u8] =
+        include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.gsub.fil.ttf");
+    static ROBOTO_FI: &[u8] =
+        include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.gsub.fi.ttf");
+    static CV01: &[u8] = include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/cv01.otf");
+    static COMPONENTS: &[u8] = include_bytes!(
+        "../../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.components.1fc.nohints.ttf"
+    );
+
+    #[test]
+    fn test_layout() {
+        let blob = Blob::new_read_only(ROBOTO_FIL);
+        let face = Face::new(&blob, 0);
+        let gsub = face.gsub();
+        assert_eq!(gsub.tag(), GSUB);
+        assert_eq!(gsub.lookup_count(), 2);
+
+        let scripts: Vec<Tag> = gsub.scripts().iter().map(|script| script.tag()).collect();
+        let expected: Vec<Tag> = ["DFLT", "cyrl", "grek", "latn"]
+            .iter()
+            .map(|tag| Tag::from_string(tag))
+            .collect();
+        assert_eq!(scripts, expected);
+
+        let latn = gsub.find_script(Tag::from_string("latn")).unwrap();
+        assert_eq!(latn.index(), 3);
+        let languages = latn.languages();
+        assert_eq!(languages.len(), 8);
+        assert!(languages[0].is_default());
+        assert_eq!(languages[0].tag(), DEFAULT_LANGUAGE);
+        assert_eq!(languages[3].tag(), Tag::from_string("FRA "));
+
+        let trk = latn.find_language(Tag::from_string("TRK ")).unwrap();
+        assert_eq!(trk.index(), 6);
+        assert!(trk.required_feature().is_none());
+        let features = trk.features();
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0].index(), 0);
+        assert_eq!(features[0].lookups(), vec![1]);
+        assert_eq!(
+            trk.find_feature(Tag::from_string("liga")).unwrap().index(),
+            0
+        );
+        assert!(trk.find_feature(Tag::from_string("kern")).is_none());
+        assert!(latn.find_language(Tag::from_string("DEU ")).is_none());
+
+        let liga = latn.default_language().unwrap().features()[0];
+        assert_eq!(liga.index(), 2);
+        assert_eq!(liga.lookups(), vec![0, 1]);
+        assert_eq!(gsub.features().len(), 3);
+        assert_eq!(gsub.feature(1).unwrap().lookups(), vec![0, 1, 0, 1]);
+        assert!(gsub.feature(3).is_none());
+
+        let all = gsub.collect_features(None, None, None);
+        assert_eq!(all.iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+        let latn_tags = [latn.tag()];
+        let trk_tags = [trk.tag()];
+        let french = gsub.collect_features(Some(&latn_tags), Some(&trk_tags), None);
+        assert_eq!(french.iter().collect::<Vec<_>>(), vec![0]);
+        let lookups = gsub.collect_lookups(Some(&latn_tags), None, None);
+        assert_eq!(lookups.iter().collect::<Vec<_>>(), vec![0, 1]);
+        assert!(gsub
+            .collect_lookups(Some(&latn_tags), Some(&[]), None)
+            .is_empty());
+        assert!(gsub
+            .collect_lookups(None, None, Some(&[Tag::from_string("kern")]))
+            .is_empty());
+
+        assert_eq!(face.gpos().tag(), GPOS);
+        assert_eq!(face.gpos().lookup_count(), 0);
+        assert_eq!(liga.ui_info(None), None);
+    }
+
+    #[test]
+    fn test_feature_ui_info() {
+        let blob = Blob::new_read_only(CV01);
+        let face = Face::new(&blob, 0);
+        let cv01 = face.gsub().scripts()[0]
+            .default_language()
+            .unwrap()
+            .find_feature(Tag::from_string("cv01"))
+            .unwrap();
+
+        let info = cv01.ui_info(None).unwrap();
+        assert_eq!(info.label, face.name(NameId::Other(256), None));
+        assert!(info.label.is_some());
+        assert!(info.tooltip.is_some());
+        assert!(info.sample.is_some());
+        assert_eq!(info.param_labels.len(), 2);
+        assert_eq!(info.param_labels[0], face.name(NameId::Other(259), None));
+        assert_eq!(info.characters, vec!['\n', '\u{5dde}']);
+        assert_eq!(
+            face.feature_ui_info(GSUB, cv01.index(), None),
+            Some(info.clone())
+        );
+
+        // The font only has English names.
+        let german = cv01.ui_info(Some(Language::from_string("de"))).unwrap();
+        assert_eq!(german.label, None);
+        assert_eq!(german.param_labels, vec![None, None]);
+        assert_eq!(german.characters, info.characters);
+    }
+
+    #[test]
+    fn test_missing_default_language() {
+        let blob = Blob::new_read_only(COMPONENTS);
+        let face = Face::new(&blob, 0);
+        let gsub = face.gsub();
+
+        // This script has a default language system, but no features in
+        // it, which HarfBuzz can't tell apart from a missing one.
+        let dflt = gsub.find_script(Tag::from_string("DFLT")).unwrap();
+        assert!(dflt.default_language().is_none());
+        assert!(dflt.languages().is_empty());
+
+        // This script has neither a default language system nor others.
+        let latn = gsub.find_script(Tag::from_string("latn")).unwrap();
+        assert!(latn.default_language().is_none());
+        assert!(latn.find_language(DEFAULT_LANGUAGE).is_none());
+        assert!(latn.languages().is_empty());
+    }
+
+    #[test]
+    fn test_closure() {
+        let blob = Blob::new_read_only(ROBOTO_FI);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+        let f = font.nominal_glyph('f').unwrap();
+        let i = font.nominal_glyph('i').unwrap();
+
+        let mut buffer = Buffer::with("fi");
+        buffer.guess_segment_properties();
+        let closure: Vec<u32> = font.glyphs_closure(&buffer, &[]).iter().collect();
+        assert_eq!(closure, vec![f, i, 3, 4]);
+        let no_liga = [Feature::new(Tag::from_string("liga"), 0)];
+        let closure: Vec<u32> = font.glyphs_closure(&buffer, &no_liga).iter().collect();
+        assert_eq!(closure, vec![f, i]);
+
+        assert!(face.would_substitute(0, &[f, i], true));
+        assert!(!face.would_substitute(0, &[i, f], true));
+        assert!(!face.would_substitute(0, &[f], true));
+
+        let mut glyphs: Set = vec![f, i].into_iter().collect();
+        face.lookup_substitute_closure(0, &mut glyphs);
+        assert!(glyphs.contains(3));
+        let mut glyphs: Set = vec![f, i].into_iter().collect();
+        let lookups = face.gsub().collect_lookups(None, None, None);
+        face.lookups_substitute_closure(&lookups, &mut glyphs);
+        assert!(glyphs.contains(3));
+    }
+}
@@ -0,0 +1,796 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `MATH` table, which holds the constants and glyph data needed to
+//! lay out mathematical formulas.
+//!
+//! ```
+//! # use harfbuzz::{Blob, Direction, Face, Font};
+//! # use harfbuzz::ot::math::{MathConstant, MathKern};
+//! let blob = Blob::new_read_only(&[]);
+//! let face = Face::new(&blob, 0);
+//! let font = Font::new(&face);
+//! assert!(!face.has_math_data());
+//! assert_eq!(font.math_constant(MathConstant::AxisHeight), 0);
+//! assert_eq!(font.math_kerning(0, MathKern::TopRight, 0), 0);
+//! assert_eq!(font.math_glyph_variants(0, Direction::TTB).count(), 0);
+//! ```
+
+use std::ptr;
+use sys;
+
+use super::collect_array;
+use {Direction, Face, Font};
+
+/// Identifies a constant of the `MATH` table.
+///
+/// Percentages are returned as is, and other constants are scaled by
+/// the font.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MathConstant {
+    /// The percentage to scale down the font by for the first level of
+    /// subscripts and superscripts.
+    ScriptPercentScaleDown,
+    /// The percentage to scale down the font by for the second level of
+    /// subscripts and superscripts.
+    ScriptScriptPercentScaleDown,
+    /// The minimum height of a sub-formula for its delimiters to grow.
+    DelimitedSubFormulaMinHeight,
+    /// The minimum height of n-ary operators, such as integrals and sums,
+    /// in display mode.
+    DisplayOperatorMinHeight,
+    /// The white space between the lines of a multiline formula.
+    MathLeading,
+    /// The height of the math axis, around which operators and fraction
+    /// bars are centered.
+    AxisHeight,
+    /// The maximum height of a base that does not need its accent raised.
+    AccentBaseHeight,
+    /// The minimum height of a base for which flattened accents are used.
+    FlattenedAccentBaseHeight,
+    /// The standard shift down of subscripts.
+    SubscriptShiftDown,
+    /// The maximum height of the top of a subscript.
+    SubscriptTopMax,
+    /// The minimum distance from the bottom of a base to the baseline of
+    /// its subscript.
+    SubscriptBaselineDropMin,
+    /// The standard shift up of superscripts.
+    SuperscriptShiftUp,
+    /// The standard shift up of superscripts in cramped style.
+    SuperscriptShiftUpCramped,
+    /// The minimum height of the bottom of a superscript.
+    SuperscriptBottomMin,
+    /// The maximum distance from the top of a base to the baseline of its
+    /// superscript.
+    SuperscriptBaselineDropMax,
+    /// The minimum gap between a superscript and a subscript.
+    SubSuperscriptGapMin,
+    /// The maximum height of the bottom of a superscript that has a
+    /// subscript.
+    SuperscriptBottomMaxWithSubscript,
+    /// The extra white space after a subscript or superscript.
+    SpaceAfterScript,
+    /// The minimum gap between an upper limit and its operator.
+    UpperLimitGapMin,
+    /// The minimum distance from the top of an operator to the baseline of
+    /// its upper limit.
+    UpperLimitBaselineRiseMin,
+    /// The minimum gap between a lower limit and its operator.
+    LowerLimitGapMin,
+    /// The minimum distance from the bottom of an operator to the baseline
+    /// of its lower limit.
+    LowerLimitBaselineDropMin,
+    /// The standard shift up of the top element of a stack.
+    StackTopShiftUp,
+    /// The standard shift up of the top element of a stack in display
+    /// style.
+    StackTopDisplayStyleShiftUp,
+    /// The standard shift down of the bottom element of a stack.
+    StackBottomShiftDown,
+    /// The standard shift down of the bottom element of a stack in display
+    /// style.
+    StackBottomDisplayStyleShiftDown,
+    /// The minimum gap between the elements of a stack.
+    StackGapMin,
+    /// The minimum gap between the elements of a stack in display style.
+    StackDisplayStyleGapMin,
+    /// The standard shift up of an element above a stretched glyph.
+    StretchStackTopShiftUp,
+    /// The standard shift down of an element below a stretched glyph.
+    StretchStackBottomShiftDown,
+    /// The minimum gap between a stretched glyph and the element above it.
+    StretchStackGapAboveMin,
+    /// The minimum gap between a stretched glyph and the element below it.
+    StretchStackGapBelowMin,
+    /// The standard shift up of the numerator of a fraction.
+    FractionNumeratorShiftUp,
+    /// The standard shift up of the numerator of a fraction in display
+    /// style.
+    FractionNumeratorDisplayStyleShiftUp,
+    /// The standard shift down of the denominator of a fraction.
+    FractionDenominatorShiftDown,
+    /// The standard shift down of the denominator of a fraction in display
+    /// style.
+    FractionDenominatorDisplayStyleShiftDown,
+    /// The minimum gap between the numerator of a fraction and its bar.
+    FractionNumeratorGapMin,
+    /// The minimum gap between the numerator of a fraction and its bar in
+    /// display style.
+    FractionNumDisplayStyleGapMin,
+    /// The thickness of the bar of a fraction.
+    FractionRuleThickness,
+    /// The minimum gap between the denominator of a fraction and its bar.
+    FractionDenominatorGapMin,
+    /// The minimum gap between the denominator of a fraction and its bar in
+    /// display style.
+    FractionDenomDisplayStyleGapMin,
+    /// The horizontal distance between the top and bottom elements of a
+    /// skewed fraction.
+    SkewedFractionHorizontalGap,
+    /// The vertical distance between the ink of the top and bottom elements
+    /// of a skewed fraction.
+    SkewedFractionVerticalGap,
+    /// The gap between an overbar and the top of its base.
+    OverbarVerticalGap,
+    /// The thickness of an overbar.
+    OverbarRuleThickness,
+    /// The extra white space above an overbar.
+    OverbarExtraAscender,
+    /// The gap between an underbar and the bottom of its base.
+    UnderbarVerticalGap,
+    /// The thickness of an underbar.
+    UnderbarRuleThickness,
+    /// The extra white space below an underbar.
+    UnderbarExtraDescender,
+    /// The gap between the overbar of a radical and the top of its
+    /// radicand.
+    RadicalVerticalGap,
+    /// The gap between the overbar of a radical and the top of its radicand
+    /// in display style.
+    RadicalDisplayStyleVerticalGap,
+    /// The thickness of the overbar of a radical.
+    RadicalRuleThickness,
+    /// The extra white space above the overbar of a radical.
+    RadicalExtraAscender,
+    /// The extra horizontal kern before the degree of a radical.
+    RadicalKernBeforeDegree,
+    /// The extra horizontal kern after the degree of a radical, usually
+    /// negative.
+    RadicalKernAfterDegree,
+    /// The height of the bottom of the degree of a radical, as a percentage
+    /// of the height of the radical sign.
+    RadicalDegreeBottomRaisePercent,
+}
+
+impl From<MathConstant> for sys::hb_ot_math_constant_t {
+    fn from(constant: MathConstant) -> Self {
+        match constant {
+            MathConstant::ScriptPercentScaleDown => {
+                sys::HB_OT_MATH_CONSTANT_SCRIPT_PERCENT_SCALE_DOWN
+            }
+            MathConstant::ScriptScriptPercentScaleDown => {
+                sys::HB_OT_MATH_CONSTANT_SCRIPT_SCRIPT_PERCENT_SCALE_DOWN
+            }
+            MathConstant::DelimitedSubFormulaMinHeight => {
+                sys::HB_OT_MATH_CONSTANT_DELIMITED_SUB_FORMULA_MIN_HEIGHT
+            }
+            MathConstant::DisplayOperatorMinHeight => {
+                sys::HB_OT_MATH_CONSTANT_DISPLAY_OPERATOR_MIN_HEIGHT
+            }
+            MathConstant::MathLeading => sys::HB_OT_MATH_CONSTANT_MATH_LEADING,
+            MathConstant::AxisHeight => sys::HB_OT_MATH_CONSTANT_AXIS_HEIGHT,
+            MathConstant::AccentBaseHeight => sys::HB_OT_MATH_CONSTANT_ACCENT_BASE_HEIGHT,
+            MathConstant::FlattenedAccentBaseHeight => {
+                sys::HB_OT_MATH_CONSTANT_FLATTENED_ACCENT_BASE_HEIGHT
+            }
+            MathConstant::SubscriptShiftDown => sys::HB_OT_MATH_CONSTANT_SUBSCRIPT_SHIFT_DOWN,
+            MathConstant::SubscriptTopMax => sys::HB_OT_MATH_CONSTANT_SUBSCRIPT_TOP_MAX,
+            MathConstant::SubscriptBaselineDropMin => {
+                sys::HB_OT_MATH_CONSTANT_SUBSCRIPT_BASELINE_DROP_MIN
+            }
+            MathConstant::SuperscriptShiftUp => sys::HB_OT_MATH_CONSTANT_SUPERSCRIPT_SHIFT_UP,
+            MathConstant::SuperscriptShiftUpCramped => {
+                sys::HB_OT_MATH_CONSTANT_SUPERSCRIPT_SHIFT_UP_CRAMPED
+            }
+            MathConstant::SuperscriptBottomMin => sys::HB_OT_MATH_CONSTANT_SUPERSCRIPT_BOTTOM_MIN,
+            MathConstant::SuperscriptBaselineDropMax => {
+                sys::HB_OT_MATH_CONSTANT_SUPERSCRIPT_BASELINE_DROP_MAX
+            }
+            MathConstant::SubSuperscriptGapMin => sys::HB_OT_MATH_CONSTANT_SUB_SUPERSCRIPT_GAP_MIN,
+            MathConstant::SuperscriptBottomMaxWithSubscript => {
+                sys::HB_OT_MATH_CONSTANT_SUPERSCRIPT_BOTTOM_MAX_WITH_SUBSCRIPT
+            }
+            MathConstant::SpaceAfterScript => sys::HB_OT_MATH_CONSTANT_SPACE_AFTER_SCRIPT,
+            MathConstant::UpperLimitGapMin => sys::HB_OT_MATH_CONSTANT_UPPER_LIMIT_GAP_MIN,
+            MathConstant::UpperLimitBaselineRiseMin => {
+                sys::HB_OT_MATH_CONSTANT_UPPER_LIMIT_BASELINE_RISE_MIN
+            }
+            MathConstant::LowerLimitGapMin => sys::HB_OT_MATH_CONSTANT_LOWER_LIMIT_GAP_MIN,
+            MathConstant::LowerLimitBaselineDropMin => {
+                sys::HB_OT_MATH_CONSTANT_LOWER_LIMIT_BASELINE_DROP_MIN
+            }
+            MathConstant::StackTopShiftUp => sys::HB_OT_MATH_CONSTANT_STACK_TOP_SHIFT_UP,
+            MathConstant::StackTopDisplayStyleShiftUp => {
+                sys::HB_OT_MATH_CONSTANT_STACK_TOP_DISPLAY_STYLE_SHIFT_UP
+            }
+            MathConstant::StackBottomShiftDown => sys::HB_OT_MATH_CONSTANT_STACK_BOTTOM_SHIFT_DOWN,
+            MathConstant::StackBottomDisplayStyleShiftDown => {
+                sys::HB_OT_MATH_CONSTANT_STACK_BOTTOM_DISPLAY_STYLE_SHIFT_DOWN
+            }
+            MathConstant::StackGapMin => sys::HB_OT_MATH_CONSTANT_STACK_GAP_MIN,
+            MathConstant::StackDisplayStyleGapMin => {
+                sys::HB_OT_MATH_CONSTANT_STACK_DISPLAY_STYLE_GAP_MIN
+            }
+            MathConstant::StretchStackTopShiftUp => {
+                sys::HB_OT_MATH_CONSTANT_STRETCH_STACK_TOP_SHIFT_UP
+            }
+            MathConstant::StretchStackBottomShiftDown => {
+                sys::HB_OT_MATH_CONSTANT_STRETCH_STACK_BOTTOM_SHIFT_DOWN
+            }
+            MathConstant::StretchStackGapAboveMin => {
+                sys::HB_OT_MATH_CONSTANT_STRETCH_STACK_GAP_ABOVE_MIN
+            }
+            MathConstant::StretchStackGapBelowMin => {
+                sys::HB_OT_MATH_CONSTANT_STRETCH_STACK_GAP_BELOW_MIN
+            }
+            MathConstant::FractionNumeratorShiftUp => {
+                sys::HB_OT_MATH_CONSTANT_FRACTION_NUMERATOR_SHIFT_UP
+            }
+            MathConstant::FractionNumeratorDisplayStyleShiftUp => {
+                sys::HB_OT_MATH_CONSTANT_FRACTION_NUMERATOR_DISPLAY_STYLE_SHIFT_UP
+            }
+            MathConstant::FractionDenominatorShiftDown => {
+                sys::HB_OT_MATH_CONSTANT_FRACTION_DENOMINATOR_SHIFT_DOWN
+            }
+            MathConstant::FractionDenominatorDisplayStyleShiftDown => {
+                sys::HB_OT_MATH_CONSTANT_FRACTION_DENOMINATOR_DISPLAY_STYLE_SHIFT_DOWN
+            }
+            MathConstant::FractionNumeratorGapMin => {
+                sys::HB_OT_MATH_CONSTANT_FRACTION_NUMERATOR_GAP_MIN
+            }
+            MathConstant::FractionNumDisplayStyleGapMin => {
+                sys::HB_OT_MATH_CONSTANT_FRACTION_NUM_DISPLAY_STYLE_GAP_MIN
+            }
+            MathConstant::FractionRuleThickness => sys::HB_OT_MATH_CONSTANT_FRACTION_RULE_THICKNESS,
+            MathConstant::FractionDenominatorGapMin => {
+                sys::HB_OT_MATH_CONSTANT_FRACTION_DENOMINATOR_GAP_MIN
+            }
+            MathConstant::FractionDenomDisplayStyleGapMin => {
+                sys::HB_OT_MATH_CONSTANT_FRACTION_DENOM_DISPLAY_STYLE_GAP_MIN
+            }
+            MathConstant::SkewedFractionHorizontalGap => {
+                sys::HB_OT_MATH_CONSTANT_SKEWED_FRACTION_HORIZONTAL_GAP
+            }
+            MathConstant::SkewedFractionVerticalGap => {
+                sys::HB_OT_MATH_CONSTANT_SKEWED_FRACTION_VERTICAL_GAP
+            }
+            MathConstant::OverbarVerticalGap => sys::HB_OT_MATH_CONSTANT_OVERBAR_VERTICAL_GAP,
+            MathConstant::OverbarRuleThickness => sys::HB_OT_MATH_CONSTANT_OVERBAR_RULE_THICKNESS,
+            MathConstant::OverbarExtraAscender => sys::HB_OT_MATH_CONSTANT_OVERBAR_EXTRA_ASCENDER,
+            MathConstant::UnderbarVerticalGap => sys::HB_OT_MATH_CONSTANT_UNDERBAR_VERTICAL_GAP,
+            MathConstant::UnderbarRuleThickness => sys::HB_OT_MATH_CONSTANT_UNDERBAR_RULE_THICKNESS,
+            MathConstant::UnderbarExtraDescender => {
+                sys::HB_OT_MATH_CONSTANT_UNDERBAR_EXTRA_DESCENDER
+            }
+            MathConstant::RadicalVerticalGap => sys::HB_OT_MATH_CONSTANT_RADICAL_VERTICAL_GAP,
+            MathConstant::RadicalDisplayStyleVerticalGap => {
+                sys::HB_OT_MATH_CONSTANT_RADICAL_DISPLAY_STYLE_VERTICAL_GAP
+            }
+            MathConstant::RadicalRuleThickness => sys::HB_OT_MATH_CONSTANT_RADICAL_RULE_THICKNESS,
+            MathConstant::RadicalExtraAscender => sys::HB_OT_MATH_CONSTANT_RADICAL_EXTRA_ASCENDER,
+            MathConstant::RadicalKernBeforeDegree => {
+                sys::HB_OT_MATH_CONSTANT_RADICAL_KERN_BEFORE_DEGREE
+            }
+            MathConstant::RadicalKernAfterDegree => {
+                sys::HB_OT_MATH_CONSTANT_RADICAL_KERN_AFTER_DEGREE
+            }
+            MathConstant::RadicalDegreeBottomRaisePercent => {
+                sys::HB_OT_MATH_CONSTANT_RADICAL_DEGREE_BOTTOM_RAISE_PERCENT
+            }
+        }
+    }
+}
+
+/// Identifies a corner of a glyph for math kerning.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MathKern {
+    /// The top right corner, where superscripts attach.
+    TopRight,
+    /// The top left corner, where prescripts attach.
+    TopLeft,
+    /// The bottom right corner, where subscripts attach.
+    BottomRight,
+    /// The bottom left corner, where presubscripts attach.
+    BottomLeft,
+}
+
+impl From<MathKern> for sys::hb_ot_math_kern_t {
+    fn from(kern: MathKern) -> Self {
+        match kern {
+            MathKern::TopRight => sys::HB_OT_MATH_KERN_TOP_RIGHT,
+            MathKern::TopLeft => sys::HB_OT_MATH_KERN_TOP_LEFT,
+            MathKern::BottomRight => sys::HB_OT_MATH_KERN_BOTTOM_RIGHT,
+            MathKern::BottomLeft => sys::HB_OT_MATH_KERN_BOTTOM_LEFT,
+        }
+    }
+}
+
+/// A larger variant of a glyph, for stretching it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MathGlyphVariant {
+    /// The variant glyph.
+    pub glyph: u32,
+    /// The size of the glyph along the direction it stretches in.
+    pub advance: i32,
+}
+
+/// A part of a glyph assembly.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MathGlyphPart {
+    /// The glyph of the part.
+    pub glyph: u32,
+    /// The length of the connector at the start of the part, which can
+    /// overlap the previous part.
+    pub start_connector_length: i32,
+    /// The length of the connector at the end of the part, which can
+    /// overlap the next part.
+    pub end_connector_length: i32,
+    /// The size of the part along the direction of the assembly.
+    pub full_advance: i32,
+    /// Whether the part can be repeated to make the assembly longer.
+    pub is_extender: bool,
+}
+
+/// The parts to assemble a glyph of any size from, from left to right
+/// or from bottom to top.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MathGlyphAssembly {
+    /// The parts of the assembly.
+    pub parts: Vec<MathGlyphPart>,
+    /// The italics correction of the assembled glyph.
+    pub italics_correction: i32,
+}
+
+/// How to draw a glyph stretched to a given size, as returned by
+/// [`Font::stretch_math_glyph`].
+///
+/// [`Font::stretch_math_glyph`]: ../../struct.Font.html#method.stretch_math_glyph
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StretchedGlyph {
+    /// A single glyph, which is either the original glyph or one of its
+    /// variants.
+    Variant(MathGlyphVariant),
+    /// Parts of an assembly to draw along the direction of stretching.
+    Assembly {
+        /// The glyph of each part, and its offset from the left or
+        /// bottom of the assembly.
+        parts: Vec<(u32, i32)>,
+        /// The size of the assembly.
+        advance: i32,
+        /// The italics correction of the assembly.
+        italics_correction: i32,
+    },
+}
+
+impl<'a> Face<'a> {
+    /// Returns `true` if the face has a `MATH` table.
+    pub fn has_math_data(&self) -> bool {
+        unsafe { sys::hb_ot_math_has_data(self.as_raw()) != 0 }
+    }
+
+    /// Returns `true` if `glyph` is an extended shape, such as a large
+    /// operator, whose superscripts and subscripts are positioned
+    /// differently.
+    pub fn is_math_extended_shape(&self, glyph: u32) -> bool {
+        unsafe { sys::hb_ot_math_is_glyph_extended_shape(self.as_raw(), glyph) != 0 }
+    }
+}
+
+impl<'a> Font<'a> {
+    /// Returns the value of a constant of the `MATH` table, or zero if
+    /// the font has none.
+    pub fn math_constant(&self, constant: MathConstant) -> i32 {
+        unsafe { sys::hb_ot_math_get_constant(self.as_raw(), constant.into()) }
+    }
+
+    /// Returns the italics correction of `glyph`, or zero if it has
+    /// none.
+    pub fn math_italics_correction(&self, glyph: u32) -> i32 {
+        unsafe { sys::hb_ot_math_get_glyph_italics_correction(self.as_raw(), glyph) }
+    }
+
+    /// Returns the horizontal position to attach top accents to `glyph`
+    /// at, which is the middle of its advance if the font does not
+    /// give one.
+    pub fn math_top_accent_attachment(&self, glyph: u32) -> i32 {
+        unsafe { sys::hb_ot_math_get_glyph_top_accent_attachment(self.as_raw(), glyph) }
+    }
+
+    /// Returns the kerning of a script at `corner` of `glyph`, for a
+    /// script at height `correction_height`.
+    pub fn math_kerning(&self, glyph: u32, corner: MathKern, correction_height: i32) -> i32 {
+        unsafe {
+            sys::hb_ot_math_get_glyph_kerning(
+                self.as_raw(),
+                glyph,
+                corner.into(),
+                correction_height,
+            )
+        }
+    }
+
+    /// Returns the larger variants of `glyph` for stretching it in
+    /// `direction`, from the smallest to the largest.
+    ///
+    /// The variants do not necessarily include `glyph` itself.
+    pub fn math_glyph_variants(
+        &self,
+        glyph: u32,
+        direction: Direction,
+    ) -> impl Iterator<Item = MathGlyphVariant> {
+        let variants: Vec<sys::hb_ot_math_glyph_variant_t> = unsafe {
+            collect_array(|start, count, variants| {
+                sys::hb_ot_math_get_glyph_variants(
+                    self.as_raw(),
+                    glyph,
+                    direction.into(),
+                    start,
+                    count,
+                    variants,
+                )
+            })
+        };
+        variants.into_iter().map(|variant| MathGlyphVariant {
+            glyph: variant.glyph,
+            advance: variant.advance,
+        })
+    }
+
+    /// Returns the minimum overlap of the connectors of adjacent parts
+    /// of glyph assemblies in `direction`.
+    pub fn math_min_connector_overlap(&self, direction: Direction) -> i32 {
+        unsafe { sys::hb_ot_math_get_min_connector_overlap(self.as_raw(), direction.into()) }
+    }
+
+    /// Returns the parts to assemble `glyph` from when stretching it in
+    /// `direction`, if it can be assembled.
+    pub fn math_glyph_assembly(
+        &self,
+        glyph: u32,
+        direction: Direction,
+    ) -> Option<MathGlyphAssembly> {
+        unsafe {
+            let mut count = 0;
+            let total = sys::hb_ot_math_get_glyph_assembly(
+                self.as_raw(),
+                glyph,
+                direction.into(),
+                0,
+                &mut count,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            if total == 0 {
+                return None;
+            }
+            let mut parts = Vec::with_capacity(total as usize);
+            let mut italics_correction = 0;
+            count = total;
+            sys::hb_ot_math_get_glyph_assembly(
+                self.as_raw(),
+                glyph,
+                direction.into(),
+                0,
+                &mut count,
+                parts.as_mut_ptr(),
+                &mut italics_correction,
+            );
+            parts.set_len(count as usize);
+            Some(MathGlyphAssembly {
+                parts: parts
+                    .into_iter()
+                    .map(|part: sys::hb_ot_math_glyph_part_t| MathGlyphPart {
+                        glyph: part.glyph,
+                        start_connector_length: part.start_connector_length,
+                        end_connector_length: part.end_connector_length,
+                        full_advance: part.full_advance,
+                        is_extender: part.flags & sys::HB_OT_MATH_GLYPH_PART_FLAG_EXTENDER != 0,
+                    })
+                    .collect(),
+                italics_correction,
+            })
+        }
+    }
+
+    /// Returns how to draw `glyph` stretched in `direction` to at least
+    /// `size`, such as for a delimiter around a formula.
+    ///
+    /// The smallest of `glyph` and its variants that is large enough is
+    /// used. Otherwise the glyph is assembled from its parts, with the
+    /// extenders repeated as few times as needed and the parts
+    /// overlapped evenly to come as close to `size` as the connectors
+    /// allow. Adjacent parts overlap by at least the font's minimum
+    /// connector overlap, even where their connectors are shorter. If
+    /// the glyph cannot be assembled, its largest variant is used.
+    ///
+    /// The size of `glyph` itself is its advance for horizontal
+    /// directions and the height of its extents for vertical ones.
+    ///
+    /// ```
+    /// # use harfbuzz::{Blob, Direction, Face, Font};
+    /// # use harfbuzz::ot::math::StretchedGlyph;
+    /// let blob = Blob::new_read_only(&[]);
+    /// let face = Face::new(&blob, 0);
+    /// let font = Font::new(&face);
+    /// // The empty font cannot stretch its glyphs.
+    /// match font.stretch_math_glyph(0, Direction::LTR, 5000) {
+    ///     StretchedGlyph::Variant(variant) => assert_eq!(variant.advance, 1000),
+    ///     StretchedGlyph::Assembly { .. } => unreachable!(),
+    /// }
+    /// ```
+    pub fn stretch_math_glyph(
+        &self,
+        glyph: u32,
+        direction: Direction,
+        size: i32,
+    ) -> StretchedGlyph {
+        let horizontal = direction == Direction::LTR || direction == Direction::RTL;
+        let own_size = if horizontal {
+            self.glyph_h_advance(glyph)
+        } else {
+            self.glyph_extents(glyph)
+                .map_or(0, |extents| -extents.height)
+        };
+        let variants = Some(MathGlyphVariant {
+            glyph,
+            advance: own_size,
+        })
+        .into_iter()
+        .chain(self.math_glyph_variants(glyph, direction));
+        let mut largest = None;
+        for variant in variants {
+            if variant.advance >= size {
+                return StretchedGlyph::Variant(variant);
+            }
+            largest = Some(variant);
+        }
+        match self.math_glyph_assembly(glyph, direction) {
+            Some(assembly) => {
+                let overlap = self.math_min_connector_overlap(direction);
+                assemble(&assembly, overlap, size)
+            }
+            None => StretchedGlyph::Variant(largest.unwrap()),
+        }
+    }
+}
+
+/// Lay out `assembly` to a size of at least `size`, or as close to it as
+/// possible, with adjacent parts overlapping by at least `min_overlap`,
+/// and by no more than their connectors unless those are shorter than
+/// `min_overlap`.
+fn assemble(assembly: &MathGlyphAssembly, min_overlap: i32, size: i32) -> StretchedGlyph {
+    let parts = &assembly.parts;
+    let (extenders, fixed): (Vec<&MathGlyphPart>, Vec<&MathGlyphPart>) =
+        parts.iter().partition(|part| part.is_extender);
+    let advance =
+        |parts: &[&MathGlyphPart]| -> i32 { parts.iter().map(|part| part.full_advance).sum() };
+
+    // Each repetition of the extenders adds their advance, less one
+    // overlap per part. An assembly of only extenders needs them once.
+    let mut repeats: i32 = if fixed.is_empty() { 1 } else { 0 };
+    let growth = advance(&extenders) - min_overlap * extenders.len() as i32;
+    let count = |repeats: i32| fixed.len() as i32 + repeats * extenders.len() as i32;
+    let max_size = |repeats: i32| {
+        advance(&fixed) + repeats * advance(&extenders) - min_overlap * (count(repeats) - 1)
+    };
+    if growth > 0 && max_size(repeats) < size {
+        repeats += (size - max_size(repeats) + growth - 1) / growth;
+    }
+
+    let mut sequence = Vec::new();
+    for part in parts {
+        let times = if part.is_extender { repeats } else { 1 };
+        for _ in 0..times {
+            sequence.push(part);
+        }
+    }
+
+    // Overlap the parts evenly, as much as needed to get close to
+    // `size` but no more than their connectors allow.
+    let joints = sequence.len() as i32 - 1;
+    let total: i32 = sequence.iter().map(|part| part.full_advance).sum();
+    let mut overlap = min_overlap;
+    if joints > 0 && total - joints * min_overlap > size {
+        // The minimum overlap wins over connectors that are too short.
+        let max_overlap = sequence
+            .windows(2)
+            .map(|pair| {
+                pair[0]
+                    .end_connector_length
+                    .min(pair[1].start_connector_length)
+            })
+            .min()
+            .unwrap()
+            .max(min_overlap);
+        overlap = ((total - size) / joints).min(max_overlap);
+    }
+
+    let mut offset = 0;
+    let mut placed = Vec::with_capacity(sequence.len());
+    for part in &sequence {
+        placed.push((part.glyph, offset));
+        offset += part.full_advance - overlap;
+    }
+    StretchedGlyph::Assembly {
+        parts: placed,
+        advance: total - joints.max(0) * overlap,
+        italics_correction: assembly.italics_correction,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        assemble, MathConstant, MathGlyphAssembly, MathGlyphPart, MathGlyphVariant, MathKern,
+        StretchedGlyph,
+    };
+    use {Blob, Direction, Face, Font};
+
+    static MATH_FULL: &[u8] =
+        include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/MathTestFontFull.otf");
+    static MATH_NONE: &[u8] =
+        include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/MathTestFontNone.otf");
+
+    #[test]
+    fn test_math() {
+        let blob = Blob::new_read_only(MATH_NONE);
+        let face = Face::new(&blob, 0);
+        assert!(!face.has_math_data());
+
+        let blob = Blob::new_read_only(MATH_FULL);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+        assert!(face.has_math_data());
+        assert_eq!(font.math_constant(MathConstant::AxisHeight), 400);
+        assert_eq!(font.math_constant(MathConstant::ScriptPercentScaleDown), 87);
+        assert_eq!(
+            font.math_constant(MathConstant::RadicalDegreeBottomRaisePercent),
+            65
+        );
+
+        assert_eq!(font.math_italics_correction(2), 197);
+        assert_eq!(font.math_top_accent_attachment(2), 458);
+        assert!(!face.is_math_extended_shape(2));
+        assert!(face.is_math_extended_shape(3));
+        assert_eq!(font.math_kerning(3, MathKern::TopRight, 20), 24);
+        assert_eq!(font.math_kerning(2, MathKern::BottomLeft, 20), 29);
+
+        let variants: Vec<_> = font.math_glyph_variants(30, Direction::LTR).collect();
+        assert_eq!(
+            variants[0],
+            MathGlyphVariant {
+                glyph: 65,
+                advance: 2151
+            }
+        );
+        assert_eq!(variants.len(), 3);
+        assert_eq!(font.math_glyph_variants(30, Direction::TTB).count(), 0);
+
+        assert_eq!(font.math_min_connector_overlap(Direction::TTB), 54);
+        let assembly = font.math_glyph_assembly(31, Direction::TTB).unwrap();
+        assert_eq!(assembly.italics_correction, 331);
+        assert_eq!(
+            assembly.parts[1],
+            MathGlyphPart {
+                glyph: 64,
+                start_connector_length: 296,
+                end_connector_length: 54,
+                full_advance: 1000,
+                is_extender: true,
+            }
+        );
+        assert!(!assembly.parts[0].is_extender);
+        assert_eq!(font.math_glyph_assembly(31, Direction::LTR), None);
+    }
+
+    #[test]
+    fn test_stretch_math_glyph() {
+        let blob = Blob::new_read_only(MATH_FULL);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        // The glyph itself, then its variants.
+        let stretched = font.stretch_math_glyph(30, Direction::LTR, 500);
+        assert_eq!(
+            stretched,
+            StretchedGlyph::Variant(MathGlyphVariant {
+                glyph: 30,
+                advance: 1000
+            })
+        );
+        let stretched = font.stretch_math_glyph(30, Direction::LTR, 2200);
+        assert_eq!(
+            stretched,
+            StretchedGlyph::Variant(MathGlyphVariant {
+                glyph: 66,
+                advance: 2401
+            })
+        );
+
+        // The extender is repeated three times, and the parts overlap
+        // evenly to give the exact size.
+        let stretched = font.stretch_math_glyph(32, Direction::LTR, 4500);
+        assert_eq!(
+            stretched,
+            StretchedGlyph::Assembly {
+                parts: vec![(59, 0), (63, 875), (63, 1750), (63, 2625), (60, 3500)],
+                advance: 4500,
+                italics_correction: 379,
+            }
+        );
+
+        // The connectors limit the overlap to 158.
+        match font.stretch_math_glyph(32, Direction::LTR, 6000) {
+            StretchedGlyph::Assembly { parts, advance, .. } => {
+                assert_eq!(parts.len(), 7);
+                assert_eq!(parts[1], (63, 842));
+                assert_eq!(advance, 6052);
+            }
+            stretched => panic!("unexpected {:?}", stretched),
+        }
+
+        // Vertical assemblies use the minimum overlap between extenders.
+        match font.stretch_math_glyph(31, Direction::TTB, 10000) {
+            StretchedGlyph::Assembly { parts, advance, .. } => {
+                assert_eq!(parts.len(), 11);
+                assert_eq!(advance, 10460);
+            }
+            stretched => panic!("unexpected {:?}", stretched),
+        }
+
+        // A glyph that cannot stretch stays as it is.
+        let stretched = font.stretch_math_glyph(2, Direction::TTB, 9000);
+        assert_eq!(
+            stretched,
+            StretchedGlyph::Variant(MathGlyphVariant {
+                glyph: 2,
+                advance: 1000
+            })
+        );
+    }
+
+    #[test]
+    fn test_assemble_overlap() {
+        let assembly = |connector_length| MathGlyphAssembly {
+            parts: (1..3)
+                .map(|glyph| MathGlyphPart {
+                    glyph,
+                    start_connector_length: connector_length,
+                    end_connector_length: connector_length,
+                    full_advance: 1000,
+                    is_extender: false,
+                })
+                .collect(),
+            italics_correction: 0,
+        };
+        let stretched = |overlap| StretchedGlyph::Assembly {
+            parts: vec![(1, 0), (2, 1000 - overlap)],
+            advance: 2000 - overlap,
+            italics_correction: 0,
+        };
+
+        // The parts overlap as much as their connectors allow.
+        assert_eq!(assemble(&assembly(300), 100, 1500), stretched(300));
+        assert_eq!(assemble(&assembly(300), 100, 1800), stretched(200));
+        // But always by at least the minimum, even past short connectors.
+        assert_eq!(assemble(&assembly(50), 100, 1500), stretched(100));
+        assert_eq!(assemble(&assembly(50), 100, 2500), stretched(100));
+    }
+}
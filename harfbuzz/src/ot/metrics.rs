@@ -0,0 +1,259 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Font-wide metrics from the `OS/2`, `post`, `hhea` and `vhea` tables,
+//! adjusted for the font's variations by the `MVAR` table.
+//!
+//! ```
+//! # use harfbuzz::{Blob, Face, Font};
+//! # use harfbuzz::ot::metrics::MetricsTag;
+//! let blob = Blob::new_read_only(&[]);
+//! let face = Face::new(&blob, 0);
+//! let font = Font::new(&face);
+//! assert_eq!(font.metric(MetricsTag::XHeight), None);
+//! assert_eq!(font.metrics().underline_offset, None);
+//! ```
+
+use sys;
+
+use Font;
+
+/// Identifies a font-wide metric.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MetricsTag {
+    /// The horizontal typographic ascender.
+    HorizontalAscender,
+    /// The horizontal typographic descender.
+    HorizontalDescender,
+    /// The horizontal typographic line gap.
+    HorizontalLineGap,
+    /// The horizontal clipping ascent.
+    HorizontalClippingAscent,
+    /// The horizontal clipping descent.
+    HorizontalClippingDescent,
+    /// The vertical typographic ascender.
+    VerticalAscender,
+    /// The vertical typographic descender.
+    VerticalDescender,
+    /// The vertical typographic line gap.
+    VerticalLineGap,
+    /// The rise of the slope of the caret for horizontal text.
+    HorizontalCaretRise,
+    /// The run of the slope of the caret for horizontal text.
+    HorizontalCaretRun,
+    /// The offset of the caret for horizontal text.
+    HorizontalCaretOffset,
+    /// The rise of the slope of the caret for vertical text.
+    VerticalCaretRise,
+    /// The run of the slope of the caret for vertical text.
+    VerticalCaretRun,
+    /// The offset of the caret for vertical text.
+    VerticalCaretOffset,
+    /// The height of lowercase letters.
+    XHeight,
+    /// The height of capital letters.
+    CapHeight,
+    /// The horizontal size of subscripts.
+    SubscriptEmXSize,
+    /// The vertical size of subscripts.
+    SubscriptEmYSize,
+    /// The horizontal offset of subscripts.
+    SubscriptEmXOffset,
+    /// The vertical offset of subscripts.
+    SubscriptEmYOffset,
+    /// The horizontal size of superscripts.
+    SuperscriptEmXSize,
+    /// The vertical size of superscripts.
+    SuperscriptEmYSize,
+    /// The horizontal offset of superscripts.
+    SuperscriptEmXOffset,
+    /// The vertical offset of superscripts.
+    SuperscriptEmYOffset,
+    /// The thickness of the strikeout stroke.
+    StrikeoutSize,
+    /// The position of the top of the strikeout stroke above the
+    /// baseline.
+    StrikeoutOffset,
+    /// The thickness of the underline.
+    UnderlineSize,
+    /// The position of the top of the underline above the baseline.
+    UnderlineOffset,
+}
+
+impl From<MetricsTag> for sys::hb_ot_metrics_tag_t {
+    fn from(tag: MetricsTag) -> Self {
+        match tag {
+            MetricsTag::HorizontalAscender => sys::HB_OT_METRICS_TAG_HORIZONTAL_ASCENDER,
+            MetricsTag::HorizontalDescender => sys::HB_OT_METRICS_TAG_HORIZONTAL_DESCENDER,
+            MetricsTag::HorizontalLineGap => sys::HB_OT_METRICS_TAG_HORIZONTAL_LINE_GAP,
+            MetricsTag::HorizontalClippingAscent => {
+                sys::HB_OT_METRICS_TAG_HORIZONTAL_CLIPPING_ASCENT
+            }
+            MetricsTag::HorizontalClippingDescent => {
+                sys::HB_OT_METRICS_TAG_HORIZONTAL_CLIPPING_DESCENT
+            }
+            MetricsTag::VerticalAscender => sys::HB_OT_METRICS_TAG_VERTICAL_ASCENDER,
+            MetricsTag::VerticalDescender => sys::HB_OT_METRICS_TAG_VERTICAL_DESCENDER,
+            MetricsTag::VerticalLineGap => sys::HB_OT_METRICS_TAG_VERTICAL_LINE_GAP,
+            MetricsTag::HorizontalCaretRise => sys::HB_OT_METRICS_TAG_HORIZONTAL_CARET_RISE,
+            MetricsTag::HorizontalCaretRun => sys::HB_OT_METRICS_TAG_HORIZONTAL_CARET_RUN,
+            MetricsTag::HorizontalCaretOffset => sys::HB_OT_METRICS_TAG_HORIZONTAL_CARET_OFFSET,
+            MetricsTag::VerticalCaretRise => sys::HB_OT_METRICS_TAG_VERTICAL_CARET_RISE,
+            MetricsTag::VerticalCaretRun => sys::HB_OT_METRICS_TAG_VERTICAL_CARET_RUN,
+            MetricsTag::VerticalCaretOffset => sys::HB_OT_METRICS_TAG_VERTICAL_CARET_OFFSET,
+            MetricsTag::XHeight => sys::HB_OT_METRICS_TAG_X_HEIGHT,
+            MetricsTag::CapHeight => sys::HB_OT_METRICS_TAG_CAP_HEIGHT,
+            MetricsTag::SubscriptEmXSize => sys::HB_OT_METRICS_TAG_SUBSCRIPT_EM_X_SIZE,
+            MetricsTag::SubscriptEmYSize => sys::HB_OT_METRICS_TAG_SUBSCRIPT_EM_Y_SIZE,
+            MetricsTag::SubscriptEmXOffset => sys::HB_OT_METRICS_TAG_SUBSCRIPT_EM_X_OFFSET,
+            MetricsTag::SubscriptEmYOffset => sys::HB_OT_METRICS_TAG_SUBSCRIPT_EM_Y_OFFSET,
+            MetricsTag::SuperscriptEmXSize => sys::HB_OT_METRICS_TAG_SUPERSCRIPT_EM_X_SIZE,
+            MetricsTag::SuperscriptEmYSize => sys::HB_OT_METRICS_TAG_SUPERSCRIPT_EM_Y_SIZE,
+            MetricsTag::SuperscriptEmXOffset => sys::HB_OT_METRICS_TAG_SUPERSCRIPT_EM_X_OFFSET,
+            MetricsTag::SuperscriptEmYOffset => sys::HB_OT_METRICS_TAG_SUPERSCRIPT_EM_Y_OFFSET,
+            MetricsTag::StrikeoutSize => sys::HB_OT_METRICS_TAG_STRIKEOUT_SIZE,
+            MetricsTag::StrikeoutOffset => sys::HB_OT_METRICS_TAG_STRIKEOUT_OFFSET,
+            MetricsTag::UnderlineSize => sys::HB_OT_METRICS_TAG_UNDERLINE_SIZE,
+            MetricsTag::UnderlineOffset => sys::HB_OT_METRICS_TAG_UNDERLINE_OFFSET,
+        }
+    }
+}
+
+/// The commonly used font-wide metrics of a font, as returned by
+/// [`Font::metrics`].
+///
+/// Each metric is `None` if the font does not have it.
+///
+/// [`Font::metrics`]: ../../struct.Font.html#method.metrics
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FontMetrics {
+    /// The horizontal typographic ascender.
+    pub ascender: Option<i32>,
+    /// The horizontal typographic descender.
+    pub descender: Option<i32>,
+    /// The horizontal typographic line gap.
+    pub line_gap: Option<i32>,
+    /// The height of lowercase letters.
+    pub x_height: Option<i32>,
+    /// The height of capital letters.
+    pub cap_height: Option<i32>,
+    /// The position of the top of the underline above the baseline.
+    pub underline_offset: Option<i32>,
+    /// The thickness of the underline.
+    pub underline_size: Option<i32>,
+    /// The position of the top of the strikeout stroke above the
+    /// baseline.
+    pub strikeout_offset: Option<i32>,
+    /// The thickness of the strikeout stroke.
+    pub strikeout_size: Option<i32>,
+    /// The horizontal and vertical size of subscripts.
+    pub subscript_size: Option<(i32, i32)>,
+    /// The horizontal and vertical offset of subscripts.
+    pub subscript_offset: Option<(i32, i32)>,
+    /// The horizontal and vertical size of superscripts.
+    pub superscript_size: Option<(i32, i32)>,
+    /// The horizontal and vertical offset of superscripts.
+    pub superscript_offset: Option<(i32, i32)>,
+    /// The rise and run of the slope of the caret for horizontal text.
+    pub caret_slope: Option<(i32, i32)>,
+    /// The offset of the caret for horizontal text.
+    pub caret_offset: Option<i32>,
+}
+
+impl<'a> Font<'a> {
+    /// Returns the value of the metric identified by `tag`, scaled to
+    /// the font and adjusted for its variations, or `None` if the font
+    /// does not have it.
+    pub fn metric(&self, tag: MetricsTag) -> Option<i32> {
+        let mut position = 0;
+        if unsafe { sys::hb_ot_metrics_get_position(self.as_raw(), tag.into(), &mut position) } != 0
+        {
+            Some(position)
+        } else {
+            None
+        }
+    }
+
+    /// Returns how much the metric identified by `tag` is changed by
+    /// the font's variations, in font units.
+    pub fn metric_variation(&self, tag: MetricsTag) -> f32 {
+        unsafe { sys::hb_ot_metrics_get_variation(self.as_raw(), tag.into()) }
+    }
+
+    /// Returns the commonly used font-wide metrics of the font, scaled
+    /// to the font and adjusted for its variations.
+    pub fn metrics(&self) -> FontMetrics {
+        let pair = |x, y| match (self.metric(x), self.metric(y)) {
+            (Some(x), Some(y)) => Some((x, y)),
+            _ => None,
+        };
+        FontMetrics {
+            ascender: self.metric(MetricsTag::HorizontalAscender),
+            descender: self.metric(MetricsTag::HorizontalDescender),
+            line_gap: self.metric(MetricsTag::HorizontalLineGap),
+            x_height: self.metric(MetricsTag::XHeight),
+            cap_height: self.metric(MetricsTag::CapHeight),
+            underline_offset: self.metric(MetricsTag::UnderlineOffset),
+            underline_size: self.metric(MetricsTag::UnderlineSize),
+            strikeout_offset: self.metric(MetricsTag::StrikeoutOffset),
+            strikeout_size: self.metric(MetricsTag::StrikeoutSize),
+            subscript_size: pair(MetricsTag::SubscriptEmXSize, MetricsTag::SubscriptEmYSize),
+            subscript_offset: pair(
+                MetricsTag::SubscriptEmXOffset,
+                MetricsTag::SubscriptEmYOffset,
+            ),
+            superscript_size: pair(
+                MetricsTag::SuperscriptEmXSize,
+                MetricsTag::SuperscriptEmYSize,
+            ),
+            superscript_offset: pair(
+                MetricsTag::SuperscriptEmXOffset,
+                MetricsTag::SuperscriptEmYOffset,
+            ),
+            caret_slope: pair(
+                MetricsTag::HorizontalCaretRise,
+                MetricsTag::HorizontalCaretRun,
+            ),
+            caret_offset: self.metric(MetricsTag::HorizontalCaretOffset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MetricsTag;
+    use {Blob, Face, Font};
+
+    static ROBOTO: &[u8] =
+        include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.abc.ttf");
+
+    #[test]
+    fn test_metrics() {
+        let blob = Blob::new_read_only(ROBOTO);
+        let face = Face::new(&blob, 0);
+        let mut font = Font::new(&face);
+
+        let metrics = font.metrics();
+        assert_eq!(metrics.x_height, Some(1082));
+        assert_eq!(metrics.cap_height, Some(1456));
+        assert_eq!(metrics.underline_offset, Some(-150));
+        assert_eq!(metrics.strikeout_offset, Some(512));
+        assert_eq!(metrics.superscript_offset, Some((0, 977)));
+        assert_eq!(metrics.x_height, font.metric(MetricsTag::XHeight));
+        assert_eq!(font.metric_variation(MetricsTag::XHeight), 0.0);
+
+        let upem = face.upem() as i32;
+        font.set_scale(upem * 2, upem * 2);
+        let scaled = font.metrics();
+        assert_eq!(
+            scaled.underline_size.unwrap(),
+            metrics.underline_size.unwrap() * 2
+        );
+    }
+}
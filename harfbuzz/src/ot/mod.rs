@@ -0,0 +1,40 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Access to OpenType tables of a font face.
+
+pub mod color;
+pub mod gdef;
+pub mod layout;
+pub mod math;
+pub mod metrics;
+pub mod name;
+
+use std::os::raw::c_uint;
+use std::ptr;
+
+/// Collect an array that HarfBuzz returns a page at a time.
+///
+/// `f` is called like `hb_ot_layout_table_get_script_tags`, with a
+/// start offset, the capacity of the output, which it sets to the number
+/// of elements written, and the output. It returns the total number of
+/// elements.
+pub(crate) unsafe fn collect_array<T, F>(f: F) -> Vec<T>
+where
+    T: Copy,
+    F: Fn(c_uint, *mut c_uint, *mut T) -> c_uint,
+{
+    let mut count = 0;
+    let total = f(0, &mut count, ptr::null_mut());
+    let mut array = Vec::with_capacity(total as usize);
+    count = total;
+    f(0, &mut count, array.as_mut_ptr());
+    array.set_len(count.min(total) as usize);
+    array
+}
@@ -0,0 +1,266 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `name` table, which holds the localized names of a font, such
+//! as its family and style names.
+//!
+//! ```
+//! # use harfbuzz::{Blob, Face, Language};
+//! # use harfbuzz::ot::name::NameId;
+//! let blob = Blob::new_read_only(&[]);
+//! let face = Face::new(&blob, 0);
+//! assert_eq!(face.names().count(), 0);
+//! assert_eq!(face.name(NameId::FontFamily, None), None);
+//! ```
+
+use std::os::raw::{c_char, c_uint};
+use std::{ptr, slice};
+use sys;
+
+use {Face, Language};
+
+/// Identifies a name in the `name` table.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NameId {
+    /// Copyright notice.
+    Copyright,
+    /// Font family name.
+    FontFamily,
+    /// Font subfamily name, such as "Bold Italic".
+    FontSubfamily,
+    /// Unique font identifier.
+    UniqueId,
+    /// Full font name.
+    FullName,
+    /// Version string.
+    VersionString,
+    /// PostScript name.
+    PostScriptName,
+    /// Trademark notice.
+    Trademark,
+    /// Manufacturer name.
+    Manufacturer,
+    /// Designer name.
+    Designer,
+    /// Description of the font.
+    Description,
+    /// URL of the font vendor.
+    VendorUrl,
+    /// URL of the font designer.
+    DesignerUrl,
+    /// License description.
+    License,
+    /// URL of the license.
+    LicenseUrl,
+    /// Typographic family name.
+    TypographicFamily,
+    /// Typographic subfamily name.
+    TypographicSubfamily,
+    /// Compatible full name for Macintosh.
+    MacFullName,
+    /// Sample text.
+    SampleText,
+    /// PostScript CID findfont name.
+    CidFindfontName,
+    /// WWS family name.
+    WwsFamily,
+    /// WWS subfamily name.
+    WwsSubfamily,
+    /// Light background palette name.
+    LightBackground,
+    /// Dark background palette name.
+    DarkBackground,
+    /// Variations PostScript name prefix.
+    VariationsPostScriptPrefix,
+    /// Any other name, such as the font-specific names of features,
+    /// palettes and named instances.
+    Other(sys::hb_ot_name_id_t),
+}
+
+impl From<sys::hb_ot_name_id_t> for NameId {
+    fn from(id: sys::hb_ot_name_id_t) -> Self {
+        match id {
+            sys::HB_OT_NAME_ID_COPYRIGHT => NameId::Copyright,
+            sys::HB_OT_NAME_ID_FONT_FAMILY => NameId::FontFamily,
+            sys::HB_OT_NAME_ID_FONT_SUBFAMILY => NameId::FontSubfamily,
+            sys::HB_OT_NAME_ID_UNIQUE_ID => NameId::UniqueId,
+            sys::HB_OT_NAME_ID_FULL_NAME => NameId::FullName,
+            sys::HB_OT_NAME_ID_VERSION_STRING => NameId::VersionString,
+            sys::HB_OT_NAME_ID_POSTSCRIPT_NAME => NameId::PostScriptName,
+            sys::HB_OT_NAME_ID_TRADEMARK => NameId::Trademark,
+            sys::HB_OT_NAME_ID_MANUFACTURER => NameId::Manufacturer,
+            sys::HB_OT_NAME_ID_DESIGNER => NameId::Designer,
+            sys::HB_OT_NAME_ID_DESCRIPTION => NameId::Description,
+            sys::HB_OT_NAME_ID_VENDOR_URL => NameId::VendorUrl,
+            sys::HB_OT_NAME_ID_DESIGNER_URL => NameId::DesignerUrl,
+            sys::HB_OT_NAME_ID_LICENSE => NameId::License,
+            sys::HB_OT_NAME_ID_LICENSE_URL => NameId::LicenseUrl,
+            sys::HB_OT_NAME_ID_TYPOGRAPHIC_FAMILY => NameId::TypographicFamily,
+            sys::HB_OT_NAME_ID_TYPOGRAPHIC_SUBFAMILY => NameId::TypographicSubfamily,
+            sys::HB_OT_NAME_ID_MAC_FULL_NAME => NameId::MacFullName,
+            sys::HB_OT_NAME_ID_SAMPLE_TEXT => NameId::SampleText,
+            sys::HB_OT_NAME_ID_CID_FINDFONT_NAME => NameId::CidFindfontName,
+            sys::HB_OT_NAME_ID_WWS_FAMILY => NameId::WwsFamily,
+            sys::HB_OT_NAME_ID_WWS_SUBFAMILY => NameId::WwsSubfamily,
+            sys::HB_OT_NAME_ID_LIGHT_BACKGROUND => NameId::LightBackground,
+            sys::HB_OT_NAME_ID_DARK_BACKGROUND => NameId::DarkBackground,
+            sys::HB_OT_NAME_ID_VARIATIONS_PS_PREFIX => NameId::VariationsPostScriptPrefix,
+            id => NameId::Other(id),
+        }
+    }
+}
+
+impl From<NameId> for sys::hb_ot_name_id_t {
+    fn from(id: NameId) -> Self {
+        match id {
+            NameId::Copyright => sys::HB_OT_NAME_ID_COPYRIGHT,
+            NameId::FontFamily => sys::HB_OT_NAME_ID_FONT_FAMILY,
+            NameId::FontSubfamily => sys::HB_OT_NAME_ID_FONT_SUBFAMILY,
+            NameId::UniqueId => sys::HB_OT_NAME_ID_UNIQUE_ID,
+            NameId::FullName => sys::HB_OT_NAME_ID_FULL_NAME,
+            NameId::VersionString => sys::HB_OT_NAME_ID_VERSION_STRING,
+            NameId::PostScriptName => sys::HB_OT_NAME_ID_POSTSCRIPT_NAME,
+            NameId::Trademark => sys::HB_OT_NAME_ID_TRADEMARK,
+            NameId::Manufacturer => sys::HB_OT_NAME_ID_MANUFACTURER,
+            NameId::Designer => sys::HB_OT_NAME_ID_DESIGNER,
+            NameId::Description => sys::HB_OT_NAME_ID_DESCRIPTION,
+            NameId::VendorUrl => sys::HB_OT_NAME_ID_VENDOR_URL,
+            NameId::DesignerUrl => sys::HB_OT_NAME_ID_DESIGNER_URL,
+            NameId::License => sys::HB_OT_NAME_ID_LICENSE,
+            NameId::LicenseUrl => sys::HB_OT_NAME_ID_LICENSE_URL,
+            NameId::TypographicFamily => sys::HB_OT_NAME_ID_TYPOGRAPHIC_FAMILY,
+            NameId::TypographicSubfamily => sys::HB_OT_NAME_ID_TYPOGRAPHIC_SUBFAMILY,
+            NameId::MacFullName => sys::HB_OT_NAME_ID_MAC_FULL_NAME,
+            NameId::SampleText => sys::HB_OT_NAME_ID_SAMPLE_TEXT,
+            NameId::CidFindfontName => sys::HB_OT_NAME_ID_CID_FINDFONT_NAME,
+            NameId::WwsFamily => sys::HB_OT_NAME_ID_WWS_FAMILY,
+            NameId::WwsSubfamily => sys::HB_OT_NAME_ID_WWS_SUBFAMILY,
+            NameId::LightBackground => sys::HB_OT_NAME_ID_LIGHT_BACKGROUND,
+            NameId::DarkBackground => sys::HB_OT_NAME_ID_DARK_BACKGROUND,
+            NameId::VariationsPostScriptPrefix => sys::HB_OT_NAME_ID_VARIATIONS_PS_PREFIX,
+            NameId::Other(id) => id,
+        }
+    }
+}
+
+/// A name available in a face, as returned by [`Face::names`].
+///
+/// [`Face::names`]: ../../struct.Face.html#method.names
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NameEntry {
+    /// The identifier of the name.
+    pub name_id: NameId,
+    /// The language the name is available in.
+    pub language: Language,
+}
+
+impl<'a> Face<'a> {
+    /// Returns the names in the face's `name` table, with the
+    /// languages they are available in.
+    ///
+    /// Names with an unsupported encoding are skipped.
+    pub fn names(&self) -> impl Iterator<Item = NameEntry> {
+        let entries = unsafe {
+            let mut len = 0;
+            let ptr = sys::hb_ot_name_list_names(self.as_raw(), &mut len);
+            if ptr.is_null() {
+                &[]
+            } else {
+                slice::from_raw_parts(ptr, len as usize)
+            }
+        };
+        entries
+            .iter()
+            .map(|entry| NameEntry {
+                name_id: entry.name_id.into(),
+                language: unsafe { Language::from_raw(entry.language) },
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Returns the name identified by `name_id` in `language`.
+    ///
+    /// As in HarfBuzz, the language must match exactly, and English is
+    /// used if `language` is `None`. The languages a name is available
+    /// in are listed by [`Face::names`].
+    ///
+    /// [`Face::names`]: #method.names
+    pub fn name(&self, name_id: NameId, language: Option<Language>) -> Option<String> {
+        let language = language.unwrap_or_else(|| Language::from_string("en"));
+        unsafe {
+            let len = sys::hb_ot_name_get_utf8(
+                self.as_raw(),
+                name_id.into(),
+                language.as_raw(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            if len == 0 {
+                return None;
+            }
+            // Leave room for the terminating NUL.
+            let mut text = vec![0u8; len as usize + 1];
+            let mut size = len + 1;
+            sys::hb_ot_name_get_utf8(
+                self.as_raw(),
+                name_id.into(),
+                language.as_raw(),
+                &mut size as *mut c_uint,
+                text.as_mut_ptr() as *mut c_char,
+            );
+            text.truncate(size as usize);
+            String::from_utf8(text).ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NameId;
+    use {Blob, Face, Language};
+
+    static CV01: &[u8] = include_bytes!("../../../harfbuzz-sys/harfbuzz/test/api/fonts/cv01.otf");
+
+    #[test]
+    fn test_names() {
+        let blob = Blob::new_read_only(CV01);
+        let face = Face::new(&blob, 0);
+
+        let names: Vec<_> = face.names().collect();
+        assert_eq!(names.len(), 12);
+        assert_eq!(names[3].name_id, NameId::UniqueId);
+        assert_eq!(names[3].language.to_string(), "en");
+        assert!(names
+            .iter()
+            .any(|entry| entry.name_id == NameId::Other(256)));
+
+        assert_eq!(
+            face.name(NameId::UniqueId, Some(names[3].language))
+                .unwrap()
+                .len(),
+            27
+        );
+        assert_eq!(
+            face.name(NameId::UniqueId, None),
+            face.name(NameId::UniqueId, Some(Language::from_string("en")))
+        );
+
+        // Languages must match exactly.
+        for language in &["en-GB", "de"] {
+            let language = Some(Language::from_string(language));
+            assert_eq!(face.name(NameId::UniqueId, language), None);
+        }
+        assert_eq!(face.name(NameId::SampleText, None), None);
+
+        for id in 0..300 {
+            assert_eq!(u32::from(NameId::from(id)), id);
+        }
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ops::Range;
+use sys;
+
+#[cfg(not(feature = "unicode-bidi"))]
+use itemize;
+#[cfg(feature = "unicode-bidi")]
+use itemize_bidi;
+use {shape, Buffer, Direction, Feature, Font, Item, Language, SegmentProperties};
+
+/// A run of glyphs shaped with a single set of segment properties.
+#[derive(Clone, Debug)]
+pub struct GlyphRun {
+    /// The byte range of the run in the shaped text.
+    pub range: Range<usize>,
+    /// The segment properties the run was shaped with.
+    pub props: SegmentProperties,
+    /// The bidi embedding level of the run. Odd levels are right to
+    /// left.
+    pub level: u8,
+    /// The glyphs of the run, in visual order. Clusters are byte
+    /// offsets into the shaped text.
+    pub infos: Vec<sys::hb_glyph_info_t>,
+    /// The positions of the glyphs of the run.
+    pub positions: Vec<sys::hb_glyph_position_t>,
+    /// The sum of the horizontal advances of the glyphs.
+    pub advance: i32,
+}
+
+impl GlyphRun {
+    /// Shape `item` of `text`, with the rest of the text as context.
+    pub(crate) fn shape(text: &str, item: Item, font: &Font, features: &[Feature]) -> GlyphRun {
+        let mut buffer = Buffer::new();
+        buffer.add_str_item(text, item.range.clone());
+        buffer.set_segment_properties(&item.props);
+        shape(font, &mut buffer, features);
+
+        let positions = buffer.glyph_positions().to_vec();
+        GlyphRun {
+            range: item.range,
+            props: item.props,
+            level: item.level,
+            infos: buffer.glyph_infos().to_vec(),
+            advance: positions.iter().map(|position| position.x_advance).sum(),
+            positions,
+        }
+    }
+}
+
+/// Shape a paragraph of text with `font`, returning its runs in visual
+/// order.
+///
+/// The text is split into runs of a single script and direction with
+/// [`itemize`], or with [`itemize_bidi`] if the `unicode-bidi` feature
+/// is enabled. Each run is shaped with the rest of the paragraph as
+/// context, and the runs are then reordered by their bidi levels. The
+/// process default language is used for all runs.
+///
+/// If `base_direction` is `None`, it is taken from the text.
+///
+/// ```
+/// # use harfbuzz::{shape_paragraph, Blob, Direction, Face, Font};
+/// let blob = Blob::new_read_only(&[]);
+/// let face = Face::new(&blob, 0);
+/// let font = Font::new(&face);
+///
+/// let runs = shape_paragraph("Hello", &font, Some(Direction::LTR), &[]);
+/// assert_eq!(runs.len(), 1);
+/// assert_eq!(runs[0].range, 0..5);
+/// assert_eq!(runs[0].infos.len(), 5);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `base_direction` is not `LTR` or `RTL`.
+///
+/// [`itemize`]: fn.itemize.html
+/// [`itemize_bidi`]: fn.itemize_bidi.html
+pub fn shape_paragraph(
+    text: &str,
+    font: &Font,
+    base_direction: Option<Direction>,
+    features: &[Feature],
+) -> Vec<GlyphRun> {
+    let language = Language::get_process_default();
+    let mut runs: Vec<GlyphRun> = paragraph_items(text, base_direction, language)
+        .into_iter()
+        .map(|item| GlyphRun::shape(text, item, font, features))
+        .collect();
+    reorder(&mut runs, |run| run.level);
+    runs
+}
+
+#[cfg(feature = "unicode-bidi")]
+fn paragraph_items(text: &str, base_direction: Option<Direction>, language: Language) -> Vec<Item> {
+    itemize_bidi(text, base_direction, language)
+}
+
+#[cfg(not(feature = "unicode-bidi"))]
+fn paragraph_items(text: &str, base_direction: Option<Direction>, language: Language) -> Vec<Item> {
+    let mut items = itemize(text, language);
+    let base_direction = base_direction
+        .or_else(|| items.first().map(|item| item.props.direction))
+        .unwrap_or(Direction::LTR);
+    match base_direction {
+        Direction::LTR => {}
+        // Left-to-right runs are embedded one level deeper in
+        // right-to-left paragraphs.
+        Direction::RTL => {
+            for item in items.iter_mut().filter(|item| item.level == 0) {
+                item.level = 2;
+            }
+        }
+        _ => panic!("the base direction must be horizontal"),
+    }
+    items
+}
+
+/// Reorder `runs` from logical to visual order, following rule L2 of
+/// the Unicode Bidirectional Algorithm.
+///
+/// From the highest level down to the lowest odd level, each sequence
+/// of runs at that level or higher is reversed.
+pub(crate) fn reorder<T, F: Fn(&T) -> u8>(runs: &mut [T], level: F) {
+    let max = match runs.iter().map(&level).max() {
+        Some(max) => max,
+        None => return,
+    };
+    let min_odd = match runs.iter().map(&level).min() {
+        Some(min) => min | 1,
+        None => return,
+    };
+    for current in (min_odd..=max).rev() {
+        let mut i = 0;
+        while i < runs.len() {
+            if level(&runs[i]) < current {
+                i += 1;
+                continue;
+            }
+            let end = i + runs[i..]
+                .iter()
+                .take_while(|run| level(run) >= current)
+                .count();
+            runs[i..end].reverse();
+            i = end;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::reorder;
+
+    #[test]
+    fn test_reorder() {
+        let mut levels: Vec<(usize, u8)> = vec![0, 1, 1, 2, 2, 1, 0, 3, 2]
+            .into_iter()
+            .enumerate()
+            .collect();
+        reorder(&mut levels, |&(_, level)| level);
+        let order: Vec<usize> = levels.iter().map(|&(i, _)| i).collect();
+        assert_eq!(order, vec![0, 5, 3, 4, 2, 1, 6, 7, 8]);
+
+        let mut empty: Vec<u8> = vec![];
+        reorder(&mut empty, |&level| level);
+    }
+
+    #[cfg(feature = "unicode-bidi")]
+    #[test]
+    fn test_shape_paragraph() {
+        use super::shape_paragraph;
+        use {Blob, Direction, Face, Font};
+
+        static ROBOTO_ABC: &[u8] =
+            include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.abc.ttf");
+        let blob = Blob::new_read_only(ROBOTO_ABC);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        let text = "ab אבג c";
+        let runs = shape_paragraph(text, &font, Some(Direction::RTL), &[]);
+        let visual: Vec<&str> = runs.iter().map(|run| &text[run.range.clone()]).collect();
+        assert_eq!(visual, vec!["c", "אבג ", " ", "ab"]);
+
+        let hebrew = &runs[1];
+        assert_eq!(hebrew.props.direction, Direction::RTL);
+        let clusters: Vec<u32> = hebrew.infos.iter().map(|info| info.cluster).collect();
+        assert_eq!(clusters, vec![9, 7, 5, 3]);
+
+        let latin = &runs[3];
+        assert_eq!(latin.level, 2);
+        assert!(latin.advance > 0);
+        assert_eq!(
+            latin.advance,
+            latin.positions.iter().map(|p| p.x_advance).sum::<i32>()
+        );
+    }
+
+    #[cfg(not(feature = "unicode-bidi"))]
+    #[test]
+    fn test_shape_paragraph_without_bidi() {
+        use super::{shape_paragraph, GlyphRun};
+        use {Blob, Direction, Face, Font};
+
+        static ROBOTO_ABC: &[u8] =
+            include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.abc.ttf");
+        let blob = Blob::new_read_only(ROBOTO_ABC);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        let clusters =
+            |run: &GlyphRun| -> Vec<u32> { run.infos.iter().map(|info| info.cluster).collect() };
+
+        // A single-direction paragraph is a single run.
+        let runs = shape_paragraph("אבג", &font, None, &[]);
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].range, 0..6);
+        assert_eq!(runs[0].props.direction, Direction::RTL);
+        assert_eq!(clusters(&runs[0]), vec![4, 2, 0]);
+
+        // Without bidi, runs take their direction from their script.
+        let text = "ab אבג c";
+        let runs = shape_paragraph(text, &font, None, &[]);
+        let visual: Vec<&str> = runs.iter().map(|run| &text[run.range.clone()]).collect();
+        assert_eq!(visual, vec!["ab ", "אבג ", "c"]);
+        assert_eq!(clusters(&runs[0]), vec![0, 1, 2]);
+        assert_eq!(clusters(&runs[1]), vec![9, 7, 5, 3]);
+        assert_eq!(clusters(&runs[2]), vec![10]);
+
+        let runs = shape_paragraph(text, &font, Some(Direction::RTL), &[]);
+        let visual: Vec<&str> = runs.iter().map(|run| &text[run.range.clone()]).collect();
+        assert_eq!(visual, vec!["c", "אבג ", "ab "]);
+        let levels: Vec<u8> = runs.iter().map(|run| run.level).collect();
+        assert_eq!(levels, vec![2, 1, 2]);
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::hash::{Hash, Hasher};
+use std::ptr;
+use sys;
+
+use {Direction, Language};
+
+/// The properties of a segment of text that influence how it is shaped.
+///
+/// See the [`Buffer`] documentation for a description of each
+/// property.
+///
+/// Equality and hashing match `hb_segment_properties_equal` and
+/// `hb_segment_properties_hash`, so segment properties can be used as
+/// keys for caching shape plans or shaping results:
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use harfbuzz::{Buffer, SegmentProperties};
+/// let mut cache = HashMap::new();
+/// for text in &["Hello", "World", "مرحبا"] {
+///     let mut b = Buffer::with(text);
+///     b.guess_segment_properties();
+///     cache.entry(b.segment_properties()).or_insert_with(Vec::new).push(*text);
+/// }
+/// assert_eq!(cache.len(), 2);
+/// ```
+///
+/// [`Buffer`]: struct.Buffer.html
+#[derive(Copy, Clone, Debug)]
+pub struct SegmentProperties {
+    /// The direction in which the output glyphs flow.
+    pub direction: Direction,
+    /// The script of the text.
+    pub script: sys::hb_script_t,
+    /// The language of the text.
+    pub language: Language,
+}
+
+impl PartialEq for SegmentProperties {
+    fn eq(&self, other: &SegmentProperties) -> bool {
+        let (a, b) = ((*self).into(), (*other).into());
+        unsafe { sys::hb_segment_properties_equal(&a, &b) != 0 }
+    }
+}
+
+impl Eq for SegmentProperties {}
+
+impl Hash for SegmentProperties {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let props = (*self).into();
+        state.write_u32(unsafe { sys::hb_segment_properties_hash(&props) });
+    }
+}
+
+impl From<sys::hb_segment_properties_t> for SegmentProperties {
+    fn from(props: sys::hb_segment_properties_t) -> Self {
+        SegmentProperties {
+            direction: props.direction.into(),
+            script: props.script,
+            language: unsafe { Language::from_raw(props.language) },
+        }
+    }
+}
+
+impl From<SegmentProperties> for sys::hb_segment_properties_t {
+    fn from(props: SegmentProperties) -> Self {
+        sys::hb_segment_properties_t {
+            direction: props.direction.into(),
+            script: props.script,
+            language: props.language.as_raw(),
+            reserved1: ptr::null_mut(),
+            reserved2: ptr::null_mut(),
+        }
+    }
+}
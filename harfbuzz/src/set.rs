@@ -0,0 +1,440 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A set of integers, and iterators over its values.
+
+use std;
+use std::iter::FromIterator;
+use std::ops::{self, RangeInclusive};
+use sys;
+
+/// The value HarfBuzz uses to mark the start and end of iteration.
+const INVALID: u32 = u32::MAX;
+
+/// A set of integers, such as codepoints or glyph indices.
+///
+/// Sets are stored as ranges of bitmaps, so they can efficiently hold
+/// large, dense collections of values. Many HarfBuzz queries return
+/// their results as a `Set`.
+///
+/// The value `u32::MAX` is reserved and can never be a member.
+///
+/// ```
+/// # use harfbuzz::Set;
+/// let mut set: Set = vec![1, 2, 3, 10].into_iter().collect();
+/// set.insert_range(20..=25);
+/// assert!(set.contains(2));
+/// assert_eq!(set.len(), 10);
+/// assert_eq!(set.ranges().collect::<Vec<_>>(), vec![1..=3, 10..=10, 20..=25]);
+///
+/// let odd: Set = (0..30).filter(|v| v % 2 == 1).collect();
+/// assert_eq!((&set & &odd).iter().collect::<Vec<_>>(), vec![1, 3, 21, 23, 25]);
+/// assert_eq!((&set - &odd).len(), 5);
+/// ```
+pub struct Set {
+    raw: *mut sys::hb_set_t,
+}
+
+impl Set {
+    /// Create a new, empty set.
+    pub fn new() -> Self {
+        Set::default()
+    }
+
+    /// Construct a `Set` from a raw pointer. Takes ownership of the set.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid `hb_set_t` that the caller owns a reference
+    /// to, which is released when the `Set` is dropped.
+    pub unsafe fn from_raw(raw: *mut sys::hb_set_t) -> Self {
+        Set { raw }
+    }
+
+    /// Borrows a raw pointer to the set.
+    pub fn as_raw(&self) -> *mut sys::hb_set_t {
+        self.raw
+    }
+
+    /// Gives up ownership and returns a raw pointer to the set.
+    pub fn into_raw(self) -> *mut sys::hb_set_t {
+        let raw = self.raw;
+        std::mem::forget(self);
+        raw
+    }
+
+    /// Returns `false` if any memory allocation for the set has failed.
+    ///
+    /// Once an allocation has failed the set is in an unusable state,
+    /// and all further operations on it are ignored.
+    pub fn allocation_successful(&self) -> bool {
+        unsafe { sys::hb_set_allocation_successful(self.raw) != 0 }
+    }
+
+    /// Remove all values from the set.
+    pub fn clear(&mut self) {
+        unsafe { sys::hb_set_clear(self.raw) };
+    }
+
+    /// Returns `true` if the set contains no values.
+    pub fn is_empty(&self) -> bool {
+        unsafe { sys::hb_set_is_empty(self.raw) != 0 }
+    }
+
+    /// Returns the number of values in the set.
+    pub fn len(&self) -> usize {
+        unsafe { sys::hb_set_get_population(self.raw) as usize }
+    }
+
+    /// Returns `true` if the set contains `value`.
+    pub fn contains(&self, value: u32) -> bool {
+        unsafe { sys::hb_set_has(self.raw, value) != 0 }
+    }
+
+    /// Add `value` to the set.
+    pub fn insert(&mut self, value: u32) {
+        unsafe { sys::hb_set_add(self.raw, value) };
+    }
+
+    /// Add all values in `range` to the set.
+    pub fn insert_range(&mut self, range: RangeInclusive<u32>) {
+        unsafe { sys::hb_set_add_range(self.raw, *range.start(), *range.end()) };
+    }
+
+    /// Remove `value` from the set.
+    pub fn remove(&mut self, value: u32) {
+        unsafe { sys::hb_set_del(self.raw, value) };
+    }
+
+    /// Remove all values in `range` from the set.
+    pub fn remove_range(&mut self, range: RangeInclusive<u32>) {
+        unsafe { sys::hb_set_del_range(self.raw, *range.start(), *range.end()) };
+    }
+
+    /// Returns the smallest value in the set, if any.
+    pub fn min(&self) -> Option<u32> {
+        match unsafe { sys::hb_set_get_min(self.raw) } {
+            INVALID => None,
+            value => Some(value),
+        }
+    }
+
+    /// Returns the largest value in the set, if any.
+    pub fn max(&self) -> Option<u32> {
+        match unsafe { sys::hb_set_get_max(self.raw) } {
+            INVALID => None,
+            value => Some(value),
+        }
+    }
+
+    /// Returns `true` if every value in this set is also in `other`.
+    pub fn is_subset(&self, other: &Set) -> bool {
+        unsafe { sys::hb_set_is_subset(self.raw, other.raw) != 0 }
+    }
+
+    /// Returns an iterator over the values of the set, in ascending
+    /// order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            set: self,
+            front: INVALID,
+            back: INVALID,
+        }
+    }
+
+    /// Returns an iterator over the contiguous ranges of values in the
+    /// set, in ascending order.
+    ///
+    /// ```
+    /// # use harfbuzz::Set;
+    /// let set: Set = vec![1, 2, 3, 7, 8].into_iter().collect();
+    /// assert_eq!(set.ranges().rev().next(), Some(7..=8));
+    /// ```
+    pub fn ranges(&self) -> Ranges<'_> {
+        Ranges {
+            set: self,
+            front: INVALID,
+            back: INVALID,
+        }
+    }
+}
+
+/// An iterator over the values of a [`Set`].
+///
+/// [`Set`]: struct.Set.html
+pub struct Iter<'a> {
+    set: &'a Set,
+    front: u32,
+    back: u32,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        let mut value = self.front;
+        if unsafe { sys::hb_set_next(self.set.raw, &mut value) } == 0 {
+            return None;
+        }
+        if self.back != INVALID && value >= self.back {
+            return None;
+        }
+        self.front = value;
+        Some(value)
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<u32> {
+        let mut value = self.back;
+        if unsafe { sys::hb_set_previous(self.set.raw, &mut value) } == 0 {
+            return None;
+        }
+        if self.front != INVALID && value <= self.front {
+            return None;
+        }
+        self.back = value;
+        Some(value)
+    }
+}
+
+/// An iterator over the contiguous ranges of values in a [`Set`].
+///
+/// [`Set`]: struct.Set.html
+pub struct Ranges<'a> {
+    set: &'a Set,
+    front: u32,
+    back: u32,
+}
+
+impl<'a> Iterator for Ranges<'a> {
+    type Item = RangeInclusive<u32>;
+
+    fn next(&mut self) -> Option<RangeInclusive<u32>> {
+        let (mut first, mut last) = (INVALID, self.front);
+        if unsafe { sys::hb_set_next_range(self.set.raw, &mut first, &mut last) } == 0 {
+            return None;
+        }
+        if self.back != INVALID && first >= self.back {
+            return None;
+        }
+        self.front = last;
+        Some(first..=last)
+    }
+}
+
+impl<'a> DoubleEndedIterator for Ranges<'a> {
+    fn next_back(&mut self) -> Option<RangeInclusive<u32>> {
+        let (mut first, mut last) = (self.back, INVALID);
+        if unsafe { sys::hb_set_previous_range(self.set.raw, &mut first, &mut last) } == 0 {
+            return None;
+        }
+        if self.front != INVALID && last <= self.front {
+            return None;
+        }
+        self.back = first;
+        Some(first..=last)
+    }
+}
+
+/// An owning iterator over the values of a [`Set`].
+///
+/// [`Set`]: struct.Set.html
+pub struct IntoIter {
+    set: Set,
+    front: u32,
+}
+
+impl Iterator for IntoIter {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if unsafe { sys::hb_set_next(self.set.raw, &mut self.front) } != 0 {
+            Some(self.front)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Set {
+    type Item = u32;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Set {
+    type Item = u32;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter {
+            set: self,
+            front: INVALID,
+        }
+    }
+}
+
+impl Extend<u32> for Set {
+    fn extend<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl FromIterator<u32> for Set {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut set = Set::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl PartialEq for Set {
+    fn eq(&self, other: &Set) -> bool {
+        unsafe { sys::hb_set_is_equal(self.raw, other.raw) != 0 }
+    }
+}
+
+impl Eq for Set {}
+
+macro_rules! set_operator {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident, $func:path) => {
+        impl<'a> ops::$op_assign<&'a Set> for Set {
+            fn $method_assign(&mut self, other: &'a Set) {
+                unsafe { $func(self.raw, other.raw) };
+            }
+        }
+
+        impl<'a, 'b> ops::$op<&'b Set> for &'a Set {
+            type Output = Set;
+
+            fn $method(self, other: &'b Set) -> Set {
+                let mut result = self.clone();
+                ops::$op_assign::$method_assign(&mut result, other);
+                result
+            }
+        }
+    };
+}
+
+set_operator!(BitOr, bitor, BitOrAssign, bitor_assign, sys::hb_set_union);
+set_operator!(
+    BitAnd,
+    bitand,
+    BitAndAssign,
+    bitand_assign,
+    sys::hb_set_intersect
+);
+set_operator!(Sub, sub, SubAssign, sub_assign, sys::hb_set_subtract);
+set_operator!(
+    BitXor,
+    bitxor,
+    BitXorAssign,
+    bitxor_assign,
+    sys::hb_set_symmetric_difference
+);
+
+impl Clone for Set {
+    /// Create a new set holding a copy of the values in this one.
+    fn clone(&self) -> Self {
+        let set = Set::new();
+        unsafe { sys::hb_set_set(set.raw, self.raw) };
+        set
+    }
+}
+
+impl std::fmt::Debug for Set {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl Default for Set {
+    /// Create a new, empty set.
+    fn default() -> Self {
+        Set {
+            raw: unsafe { sys::hb_set_create() },
+        }
+    }
+}
+
+// A set is only ever modified through a mutable reference, but reading
+// it may update a cached population count, so it is not `Sync`.
+unsafe impl Send for Set {}
+
+impl Drop for Set {
+    fn drop(&mut self) {
+        unsafe { sys::hb_set_destroy(self.raw) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Set;
+
+    #[test]
+    fn test_iteration() {
+        let set: Set = vec![5, 1, 2, 3, 100, 101].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![1, 2, 3, 5, 100, 101]);
+        assert_eq!(
+            set.iter().rev().collect::<Vec<_>>(),
+            vec![101, 100, 5, 3, 2, 1]
+        );
+        assert_eq!(
+            set.clone().into_iter().collect::<Vec<_>>(),
+            vec![1, 2, 3, 5, 100, 101]
+        );
+        assert_eq!((set.min(), set.max()), (Some(1), Some(101)));
+
+        let mut ranges = set.ranges();
+        assert_eq!(ranges.next(), Some(1..=3));
+        assert_eq!(ranges.next_back(), Some(100..=101));
+        assert_eq!(ranges.next_back(), Some(5..=5));
+        assert_eq!(ranges.next(), None);
+        assert_eq!(ranges.next_back(), None);
+
+        let mut values = set.iter();
+        assert_eq!(values.next_back(), Some(101));
+        assert_eq!(values.next(), Some(1));
+        assert_eq!(values.by_ref().count(), 4);
+        assert_eq!(values.next_back(), None);
+
+        let empty = Set::new();
+        assert_eq!(empty.iter().next(), None);
+        assert_eq!(empty.ranges().next_back(), None);
+        assert_eq!((empty.min(), empty.max()), (None, None));
+    }
+
+    #[test]
+    fn test_algebra() {
+        let a: Set = (0..10).collect();
+        let b: Set = (5..15).collect();
+        assert_eq!(&a | &b, (0..15).collect());
+        assert_eq!(&a & &b, (5..10).collect());
+        assert_eq!(&a - &b, (0..5).collect());
+        assert_eq!(&a ^ &b, (0..5).chain(10..15).collect());
+
+        let mut c = a.clone();
+        c -= &b;
+        c.insert_range(100..=102);
+        c.remove_range(0..=1);
+        c.remove(4);
+        assert_eq!(c, vec![2, 3, 100, 101, 102].into_iter().collect());
+        assert!(c.allocation_successful());
+        assert!(!c.is_subset(&a));
+        assert!((&a & &b).is_subset(&a));
+        assert_ne!(a, b);
+        assert_eq!(format!("{:?}", c), "{2, 3, 100, 101, 102}");
+    }
+}
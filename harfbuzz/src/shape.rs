@@ -0,0 +1,97 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::os::raw::c_uint;
+use std::ptr;
+use sys;
+
+use {Buffer, Feature, Font, Shaper};
+
+/// Shape the contents of `buffer` with `font`, applying `features`.
+///
+/// The buffer's segment properties must be set before shaping, for
+/// example with [`Buffer::guess_segment_properties`]. After shaping,
+/// the buffer holds glyphs rather than characters.
+///
+/// [`Buffer::guess_segment_properties`]: struct.Buffer.html#method.guess_segment_properties
+pub fn shape(font: &Font, buffer: &mut Buffer, features: &[Feature]) {
+    unsafe {
+        sys::hb_shape(
+            font.as_raw(),
+            buffer.as_ptr(),
+            Feature::slice_as_ptr(features),
+            features.len() as c_uint,
+        )
+    };
+}
+
+/// Shape the contents of `buffer` with `font` like [`shape`], trying
+/// `shapers` in order until one succeeds.
+///
+/// If `shapers` is `None`, all [available shapers] are tried. Returns
+/// `false` if none of the shapers could shape the buffer.
+///
+/// [`shape`]: fn.shape.html
+/// [available shapers]: fn.available_shapers.html
+pub fn shape_full(
+    font: &Font,
+    buffer: &mut Buffer,
+    features: &[Feature],
+    shapers: Option<&[Shaper]>,
+) -> bool {
+    let list = shapers.map(Shaper::list);
+    unsafe {
+        sys::hb_shape_full(
+            font.as_raw(),
+            buffer.as_ptr(),
+            Feature::slice_as_ptr(features),
+            features.len() as c_uint,
+            list.as_ref().map_or(ptr::null(), |list| list.as_ptr()),
+        ) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{shape, shape_full};
+    use {Blob, Buffer, Face, Font, Shaper};
+
+    static ROBOTO_FI: &[u8] =
+        include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.gsub.fi.ttf");
+
+    fn shaped_len(font: &Font, shapers: Option<&[Shaper]>) -> usize {
+        let mut buffer = Buffer::with("fi");
+        buffer.guess_segment_properties();
+        assert!(shape_full(font, &mut buffer, &[], shapers));
+        buffer.glyph_infos().len()
+    }
+
+    #[test]
+    fn test_shaper_preference() {
+        let blob = Blob::new_read_only(ROBOTO_FI);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+
+        // Only the OpenType shaper applies the ligature.
+        assert_eq!(shaped_len(&font, None), 1);
+        assert_eq!(shaped_len(&font, Some(&[Shaper::Ot])), 1);
+        assert_eq!(shaped_len(&font, Some(&[Shaper::Fallback])), 2);
+        assert_eq!(shaped_len(&font, Some(&[Shaper::Fallback, Shaper::Ot])), 2);
+
+        // An empty list tries no shapers at all.
+        let mut buffer = Buffer::with("fi");
+        buffer.guess_segment_properties();
+        assert!(!shape_full(&font, &mut buffer, &[], Some(&[])));
+
+        let mut buffer = Buffer::with("fi");
+        buffer.guess_segment_properties();
+        shape(&font, &mut buffer, &[]);
+        assert_eq!(buffer.glyph_infos().len(), 1);
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_uint};
+use std::{mem, ptr};
+use sys;
+
+use {Buffer, Face, Feature, Font, SegmentProperties, Shaper};
+
+/// A shape plan holds the decisions HarfBuzz makes about how to shape
+/// text for a given face, set of segment properties and features.
+///
+/// Building a plan involves selecting a shaper and compiling the
+/// font's lookups for the requested features, which is relatively
+/// expensive. A plan can be executed on any number of buffers that
+/// share the plan's segment properties, using any font created from
+/// the plan's face.
+///
+/// ```
+/// # use harfbuzz::{Blob, Buffer, Direction, Face, Font, Language, SegmentProperties, ShapePlan, sys};
+/// let blob = Blob::new_read_only(&[]);
+/// let face = Face::new(&blob, 0);
+/// let font = Font::new(&face);
+/// let props = SegmentProperties {
+///     direction: Direction::LTR,
+///     script: sys::HB_SCRIPT_LATIN,
+///     language: Language::from_string("en"),
+/// };
+/// let plan = ShapePlan::new(&face, &props, &[], &[], None);
+///
+/// for text in &["Hello", "World"] {
+///     let mut buffer = Buffer::with(text);
+///     buffer.set_segment_properties(&props);
+///     assert!(plan.execute(&font, &mut buffer, &[]));
+///     assert_eq!(buffer.glyph_infos().len(), 5);
+/// }
+/// ```
+pub struct ShapePlan<'a> {
+    raw: *mut sys::hb_shape_plan_t,
+    face: *mut sys::hb_face_t,
+    props: SegmentProperties,
+    phantom: PhantomData<&'a ()>,
+}
+
+impl<'a> ShapePlan<'a> {
+    /// Create a shape plan for shaping text with `props` using `face`.
+    ///
+    /// `features` are the user features the plan is compiled for, and
+    /// `coords` are the normalized variation coordinates of the fonts
+    /// it will be used with. `shapers` lists the shapers to try, in
+    /// order; `None` tries all [available shapers].
+    ///
+    /// [available shapers]: fn.available_shapers.html
+    pub fn new<'b>(
+        face: &'a Face<'b>,
+        props: &SegmentProperties,
+        features: &[Feature],
+        coords: &[i32],
+        shapers: Option<&[Shaper]>,
+    ) -> Self {
+        ShapePlan::create(
+            sys::hb_shape_plan_create2,
+            face,
+            props,
+            features,
+            coords,
+            shapers,
+        )
+    }
+
+    /// Create a shape plan like [`new`], but reuse a plan cached on the
+    /// face if one was already built for the same arguments.
+    ///
+    /// Repeatedly requesting plans for the same combination of segment
+    /// properties and features is cheap after the first call.
+    ///
+    /// [`new`]: #method.new
+    pub fn new_cached<'b>(
+        face: &'a Face<'b>,
+        props: &SegmentProperties,
+        features: &[Feature],
+        coords: &[i32],
+        shapers: Option<&[Shaper]>,
+    ) -> Self {
+        ShapePlan::create(
+            sys::hb_shape_plan_create_cached2,
+            face,
+            props,
+            features,
+            coords,
+            shapers,
+        )
+    }
+
+    fn create<'b>(
+        create: unsafe extern "C" fn(
+            *mut sys::hb_face_t,
+            *const sys::hb_segment_properties_t,
+            *const sys::hb_feature_t,
+            c_uint,
+            *const i32,
+            c_uint,
+            *const *const c_char,
+        ) -> *mut sys::hb_shape_plan_t,
+        face: &'a Face<'b>,
+        props: &SegmentProperties,
+        features: &[Feature],
+        coords: &[i32],
+        shapers: Option<&[Shaper]>,
+    ) -> Self {
+        let list = shapers.map(Shaper::list);
+        let raw_props = (*props).into();
+        let raw = unsafe {
+            create(
+                face.as_raw(),
+                &raw_props,
+                Feature::slice_as_ptr(features),
+                features.len() as c_uint,
+                coords.as_ptr(),
+                coords.len() as c_uint,
+                list.as_ref().map_or(ptr::null(), |list| list.as_ptr()),
+            )
+        };
+        ShapePlan {
+            raw,
+            face: face.as_raw(),
+            props: *props,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Borrows a raw pointer to the shape plan.
+    pub fn as_raw(&self) -> *mut sys::hb_shape_plan_t {
+        self.raw
+    }
+
+    /// Gives up ownership and returns a raw pointer to the shape plan.
+    pub fn into_raw(self) -> *mut sys::hb_shape_plan_t {
+        let raw = self.raw;
+        mem::forget(self);
+        raw
+    }
+
+    /// Returns the segment properties the plan was created for.
+    pub fn segment_properties(&self) -> SegmentProperties {
+        self.props
+    }
+
+    /// Shape the contents of `buffer` with `font`, using this plan.
+    ///
+    /// Returns `false` if shaping failed, such as when no shaper was
+    /// able to handle the font.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `font` was not created from the plan's face, if the
+    /// segment properties of `buffer` differ from the plan's, or if
+    /// `buffer` has already been shaped.
+    pub fn execute(&self, font: &Font, buffer: &mut Buffer, features: &[Feature]) -> bool {
+        if buffer.is_empty() {
+            return true;
+        }
+        unsafe {
+            assert!(
+                sys::hb_font_get_face(font.as_raw()) == self.face,
+                "font was not created from the shape plan's face"
+            );
+            assert!(
+                buffer.segment_properties() == self.props,
+                "buffer segment properties differ from the shape plan's"
+            );
+            assert_eq!(
+                sys::hb_buffer_get_content_type(buffer.as_ptr()),
+                sys::HB_BUFFER_CONTENT_TYPE_UNICODE,
+                "buffer has already been shaped"
+            );
+            sys::hb_shape_plan_execute(
+                self.raw,
+                font.as_raw(),
+                buffer.as_ptr(),
+                Feature::slice_as_ptr(features),
+                features.len() as c_uint,
+            ) != 0
+        }
+    }
+
+    /// Returns the shaper the plan will use, or `None` if none of the
+    /// requested shapers could handle the face.
+    pub fn shaper(&self) -> Option<Shaper> {
+        unsafe {
+            let name = sys::hb_shape_plan_get_shaper(self.raw);
+            if name.is_null() {
+                None
+            } else {
+                Shaper::from_name(CStr::from_ptr(name).to_str().unwrap())
+            }
+        }
+    }
+}
+
+// Shape plans are immutable once created, and reference counted
+// atomically.
+unsafe impl<'a> Send for ShapePlan<'a> {}
+unsafe impl<'a> Sync for ShapePlan<'a> {}
+
+impl<'a> Clone for ShapePlan<'a> {
+    /// Increment the reference count, sharing the underlying shape plan.
+    fn clone(&self) -> Self {
+        ShapePlan {
+            raw: unsafe { sys::hb_shape_plan_reference(self.raw) },
+            face: self.face,
+            props: self.props,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> Drop for ShapePlan<'a> {
+    /// Decrement the reference count, and destroy the shape plan if the reference count is zero.
+    fn drop(&mut self) {
+        unsafe {
+            sys::hb_shape_plan_destroy(self.raw);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShapePlan;
+    use {
+        sys, Blob, Buffer, Direction, Face, Feature, Font, Language, SegmentProperties, Shaper, Tag,
+    };
+
+    static ROBOTO_FI: &[u8] =
+        include_bytes!("../../harfbuzz-sys/harfbuzz/test/api/fonts/Roboto-Regular.gsub.fi.ttf");
+
+    fn latin() -> SegmentProperties {
+        SegmentProperties {
+            direction: Direction::LTR,
+            script: sys::HB_SCRIPT_LATIN,
+            language: Language::from_string("en"),
+        }
+    }
+
+    fn buffer(text: &str, props: &SegmentProperties) -> Buffer {
+        let mut buffer = Buffer::with(text);
+        buffer.set_segment_properties(props);
+        buffer
+    }
+
+    #[test]
+    fn test_reuse() {
+        let blob = Blob::new_read_only(ROBOTO_FI);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+        let props = latin();
+
+        let plan = ShapePlan::new(&face, &props, &[], &[], None);
+        assert_eq!(plan.shaper(), Some(Shaper::Ot));
+        let mut with_liga = buffer("fi", &props);
+        assert!(plan.execute(&font, &mut with_liga, &[]));
+        assert_eq!(with_liga.glyph_infos().len(), 1);
+
+        let no_liga = [Feature::new(Tag::from_string("liga"), 0)];
+        let plan = ShapePlan::new(&face, &props, &no_liga, &[], Some(&[Shaper::Ot]));
+        let mut without_liga = buffer("fi", &props);
+        assert!(plan.execute(&font, &mut without_liga, &no_liga));
+        assert_eq!(without_liga.glyph_infos().len(), 2);
+    }
+
+    #[test]
+    fn test_cached() {
+        let blob = Blob::new_read_only(ROBOTO_FI);
+        let face = Face::new(&blob, 0);
+        let props = latin();
+
+        let first = ShapePlan::new_cached(&face, &props, &[], &[], None);
+        let second = ShapePlan::new_cached(&face, &props, &[], &[], None);
+        assert_eq!(first.as_raw(), second.as_raw());
+        let uncached = ShapePlan::new(&face, &props, &[], &[], None);
+        assert_ne!(first.as_raw(), uncached.as_raw());
+    }
+
+    #[test]
+    #[should_panic(expected = "segment properties")]
+    fn test_mismatched_properties() {
+        let blob = Blob::new_read_only(ROBOTO_FI);
+        let face = Face::new(&blob, 0);
+        let font = Font::new(&face);
+        let plan = ShapePlan::new(&face, &latin(), &[], &[], None);
+
+        let mut rtl = latin();
+        rtl.direction = Direction::RTL;
+        plan.execute(&font, &mut buffer("fi", &rtl), &[]);
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::ptr;
+use sys;
+
+/// A shaping backend that HarfBuzz can use.
+///
+/// Which shapers are available depends on how HarfBuzz was built; see
+/// [`available_shapers`]. A list of shapers can be given to
+/// [`shape_full`] or [`ShapePlan::new`] to choose which ones to try,
+/// in order of preference:
+///
+/// ```
+/// # use harfbuzz::{shape_full, Blob, Buffer, Face, Font, Shaper};
+/// # let blob = Blob::new_read_only(&[]);
+/// # let face = Face::new(&blob, 0);
+/// # let font = Font::new(&face);
+/// let mut buffer = Buffer::with("Hello");
+/// buffer.guess_segment_properties();
+/// assert!(shape_full(&font, &mut buffer, &[], Some(&[Shaper::Ot, Shaper::Fallback])));
+/// ```
+///
+/// [`available_shapers`]: fn.available_shapers.html
+/// [`shape_full`]: fn.shape_full.html
+/// [`ShapePlan::new`]: struct.ShapePlan.html#method.new
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Shaper {
+    /// The OpenType shaper, which is always available when HarfBuzz is
+    /// built with its OpenType support.
+    Ot,
+    /// The fallback shaper, which maps characters to glyphs without
+    /// applying any layout features.
+    Fallback,
+    /// The Graphite2 shaper.
+    Graphite2,
+    /// The CoreText shaper, available on Apple platforms.
+    CoreText,
+    /// The DirectWrite shaper, available on Windows.
+    DirectWrite,
+    /// The Uniscribe shaper, available on Windows.
+    Uniscribe,
+}
+
+impl Shaper {
+    /// Returns the name HarfBuzz uses for the shaper.
+    ///
+    /// ```
+    /// # use harfbuzz::Shaper;
+    /// assert_eq!(Shaper::Ot.name(), "ot");
+    /// assert_eq!(Shaper::from_name("coretext"), Some(Shaper::CoreText));
+    /// ```
+    pub fn name(self) -> &'static str {
+        let name = self.c_name();
+        &name[..name.len() - 1]
+    }
+
+    /// Returns the shaper with the given name, if it is known.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ot" => Some(Shaper::Ot),
+            "fallback" => Some(Shaper::Fallback),
+            "graphite2" => Some(Shaper::Graphite2),
+            "coretext" => Some(Shaper::CoreText),
+            "directwrite" => Some(Shaper::DirectWrite),
+            "uniscribe" => Some(Shaper::Uniscribe),
+            _ => None,
+        }
+    }
+
+    /// The name of the shaper, including a NUL terminator.
+    fn c_name(self) -> &'static str {
+        match self {
+            Shaper::Ot => "ot\0",
+            Shaper::Fallback => "fallback\0",
+            Shaper::Graphite2 => "graphite2\0",
+            Shaper::CoreText => "coretext\0",
+            Shaper::DirectWrite => "directwrite\0",
+            Shaper::Uniscribe => "uniscribe\0",
+        }
+    }
+
+    /// Returns a NULL terminated list of shaper names, as expected by
+    /// the `shaper_list` argument of the HarfBuzz shaping functions.
+    pub(crate) fn list(shapers: &[Shaper]) -> Vec<*const c_char> {
+        shapers
+            .iter()
+            .map(|shaper| shaper.c_name().as_ptr() as *const c_char)
+            .chain(Some(ptr::null()))
+            .collect()
+    }
+}
+
+/// Returns the names of the shapers HarfBuzz was built with, in the
+/// order they are tried by default.
+///
+/// ```
+/// let shapers = harfbuzz::available_shapers();
+/// assert!(shapers.contains(&"ot"));
+/// assert!(shapers.contains(&"fallback"));
+/// ```
+pub fn available_shapers() -> Vec<&'static str> {
+    let mut shapers = Vec::new();
+    unsafe {
+        let mut list = sys::hb_shape_list_shapers();
+        while !(*list).is_null() {
+            shapers.push(CStr::from_ptr(*list).to_str().unwrap());
+            list = list.offset(1);
+        }
+    }
+    shapers
+}
@@ -0,0 +1,100 @@
+// Copyright 2018 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std;
+use sys;
+
+/// A four-byte OpenType tag, such as a table or feature name.
+///
+/// This maps to the [`hb_tag_t`] from [`harfbuzz-sys`]. It can be
+/// converted to or from `hb_tag_t` using the [`From`] and [`Into`]
+/// traits:
+///
+/// ```
+/// # use harfbuzz::{Tag, sys};
+/// let tag = Tag::new(b'G', b'S', b'U', b'B');
+/// assert_eq!(tag, Tag::from_string("GSUB"));
+/// assert_eq!(sys::hb_tag_t::from(tag), 0x4753_5542);
+/// assert_eq!(tag.to_string(), "GSUB");
+/// ```
+///
+/// [`hb_tag_t`]: ../harfbuzz_sys/type.hb_tag_t.html
+/// [`harfbuzz-sys`]: ../harfbuzz_sys/index.html
+/// [`From`]: https://doc.rust-lang.org/std/convert/trait.From.html
+/// [`Into`]: https://doc.rust-lang.org/std/convert/trait.Into.html
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tag(pub sys::hb_tag_t);
+
+impl Tag {
+    /// Create a tag from its four bytes.
+    pub const fn new(a: u8, b: u8, c: u8, d: u8) -> Self {
+        Tag(((a as u32) << 24) | ((b as u32) << 16) | ((c as u32) << 8) | (d as u32))
+    }
+
+    /// Create a tag from a string.
+    ///
+    /// Strings shorter than four bytes are padded with spaces, and
+    /// longer strings are truncated.
+    ///
+    /// ```
+    /// # use harfbuzz::Tag;
+    /// assert_eq!(Tag::from_string("CFF"), Tag::new(b'C', b'F', b'F', b' '));
+    /// ```
+    pub fn from_string(tag: &str) -> Self {
+        Tag(unsafe {
+            sys::hb_tag_from_string(
+                tag.as_ptr() as *const std::os::raw::c_char,
+                tag.len() as std::os::raw::c_int,
+            )
+        })
+    }
+
+    /// Returns the four bytes of the tag.
+    pub fn to_bytes(self) -> [u8; 4] {
+        [
+            (self.0 >> 24) as u8,
+            (self.0 >> 16) as u8,
+            (self.0 >> 8) as u8,
+            self.0 as u8,
+        ]
+    }
+}
+
+impl From<sys::hb_tag_t> for Tag {
+    fn from(tag: sys::hb_tag_t) -> Self {
+        Tag(tag)
+    }
+}
+
+impl From<Tag> for sys::hb_tag_t {
+    fn from(tag: Tag) -> Self {
+        tag.0
+    }
+}
+
+impl From<[u8; 4]> for Tag {
+    fn from(bytes: [u8; 4]) -> Self {
+        Tag::new(bytes[0], bytes[1], bytes[2], bytes[3])
+    }
+}
+
+impl std::fmt::Display for Tag {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for &b in &self.to_bytes() {
+            std::fmt::Write::write_char(fmt, b as char)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for Tag {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "Tag({:?})", self.to_string())
+    }
+}